@@ -12,6 +12,8 @@ pub enum LexerError {
     UnrecognizedToken(usize,usize,String)
 }
 
+const SEPARATORS : [char;5] = ['[', '|', ']', '+', '*'];
+
 fn eat_whitespace(src : &str, cursor : usize) -> usize {
     let Some((idx,_)) = src[cursor..].char_indices().find(|(_,char)| {
         return *char != ' ';
@@ -70,28 +72,15 @@ pub fn tokenize(src : &str) -> Result<Vec<Token>,LexerError> {
             start_column_cursor = cursor;
             continue;
         }
-        if let Some(sep_idx) = src[start_word..end_word].find("[") {
-            if sep_idx == 0 {
-                end_word = start_word + sep_idx + 1;
-            }
-            else if end_word - start_word > 1{
-                end_word = start_word + sep_idx;
-            }
-        }
-        else if let Some(sep_idx) = src[start_word..end_word].find("|") {
-            if sep_idx == 0 {
-                end_word = start_word + sep_idx + 1;
-            }
-            else if end_word - start_word > 1{
-                end_word = start_word + sep_idx;
-            }
-        }
-        else if let Some(sep_idx) = src[start_word..end_word].find("]") {
-            if sep_idx == 0 {
-                end_word = start_word + sep_idx + 1;
-            }
-            else if end_word - start_word > 1{
-                end_word = start_word + sep_idx;
+        // separators are tokens on their own, even when stuck to a word
+        if !src[start_word..end_word].starts_with('\'') {
+            if let Some(sep_idx) = src[start_word..end_word].find(&SEPARATORS[..]) {
+                if sep_idx == 0 {
+                    end_word = start_word + 1;
+                }
+                else {
+                    end_word = start_word + sep_idx;
+                }
             }
         }
 
@@ -1,6 +1,6 @@
 use std::{iter::Peekable, collections::HashMap, borrow::Borrow};
 
-use crate::vm::{vm::{Instruction,Either}, word::Word, register::Register};
+use crate::vm::{vm::{Instruction,Either,Address}, word::Word, register::Register, memory::Endian, files::FileMode};
 
 use super::{lexer::Token, token::TokenType};
 
@@ -10,6 +10,8 @@ pub struct Parser {
     tokens : Vec<Token>,
     labels : HashMap<String,usize>,
     identifier : Vec<(usize,String)>,
+    tables : HashMap<String,Vec<String>>,
+    switches : Vec<(usize,String,String)>,
     number_instructions : usize,
 }
 #[derive(Debug)]
@@ -25,6 +27,8 @@ impl Parser {
             tokens,
             labels:HashMap::new(),
             identifier:Vec::new(),
+            tables:HashMap::new(),
+            switches:Vec::new(),
             number_instructions:0,
         }
     }
@@ -48,7 +52,36 @@ impl Parser {
             let peek = peek.unwrap();
             match &peek.token {
                 TokenType::PUSH => {
-                    let some_inst = Parser::rule_push(&mut tokens);
+                    let some_inst = Parser::rule_push(&mut tokens,&self.labels);
+                    if let Ok(either) = some_inst {
+                        match either {
+                            Either::Left(inst) => {
+                                res.push(inst);
+                            }
+                            Either::Right((inst,label)) => {
+                                self.identifier.push((self.number_instructions,label));
+                                res.push(inst);
+                            }
+                        }
+                        
+                    } 
+                }
+                TokenType::PICK => {
+                    let some_inst = Parser::rule_pick(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                }
+                TokenType::ROLL => {
+                    let some_inst = Parser::rule_roll(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                }
+                TokenType::DROPN => {
+                    let some_inst = Parser::rule_dropn(&mut tokens);
                     if let Ok(inst) = some_inst {
                         res.push(inst);
                         
@@ -83,9 +116,17 @@ impl Parser {
                     } 
                 }
                 TokenType::RWRITE => {
-                    let some_inst = Parser::rule_rwrite(&mut tokens);
-                    if let Ok(inst) = some_inst {
-                        res.push(inst);
+                    let some_inst = Parser::rule_rwrite(&mut tokens,&self.labels);
+                    if let Ok(either) = some_inst {
+                        match either {
+                            Either::Left(inst) => {
+                                res.push(inst);
+                            }
+                            Either::Right((inst,label)) => {
+                                self.identifier.push((self.number_instructions,label));
+                                res.push(inst);
+                            }
+                        }
                         
                     } 
                 }
@@ -166,6 +207,65 @@ impl Parser {
                         
                     } 
                 },
+                TokenType::COPY => {
+                    let some_inst = Parser::rule_copy(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                },
+                TokenType::FILL => {
+                    let some_inst = Parser::rule_fill(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                },
+                TokenType::COMPARE => {
+                    let some_inst = Parser::rule_compare(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                },
+                TokenType::LOAD8U
+                    | TokenType::LOAD8I
+                    | TokenType::LOAD16U
+                    | TokenType::LOAD16I
+                    | TokenType::LOAD32U
+                    | TokenType::LOAD32I
+                    | TokenType::LOAD64U
+                    | TokenType::LOAD64I => {
+                    let some_inst = Parser::rule_linear_load(peek,&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                },
+                TokenType::STORE8
+                    | TokenType::STORE16
+                    | TokenType::STORE32
+                    | TokenType::STORE64 => {
+                    let some_inst = Parser::rule_linear_store(peek,&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                },
+                TokenType::MEMGROW => {
+                    let some_inst = Parser::rule_memgrow(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                },
+                TokenType::ENDIAN => {
+                    let some_inst = Parser::rule_endian(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                },
                 /* FLOW */
                 TokenType::LABEL(label) => {
                     self.labels.insert(label.to_owned(),self.number_instructions);
@@ -187,6 +287,16 @@ impl Parser {
                         
                     } 
                 }
+                TokenType::TABLE => {
+                    let (name,table) = Parser::rule_table(&mut tokens)?;
+                    self.tables.insert(name,table);
+                    continue;
+                }
+                TokenType::SWITCH => {
+                    let (table,default) = Parser::rule_switch(&mut tokens)?;
+                    self.switches.push((self.number_instructions,table,default));
+                    res.push(Instruction::SWITCH(Vec::new(),0));
+                }
                 TokenType::GOIF => {
                     let some_inst = Parser::rule_goif(&mut tokens,&self.labels);
                     if let Ok(either) = some_inst {
@@ -217,6 +327,51 @@ impl Parser {
                         
                     } 
                 }
+                TokenType::GOEQ
+                    | TokenType::GONE
+                    | TokenType::GOLT
+                    | TokenType::GOLE
+                    | TokenType::GOGT
+                    | TokenType::GOGE
+                    | TokenType::GOZ
+                    | TokenType::GONZ
+                    | TokenType::LOOP => {
+                    let some_inst = Parser::rule_gocmp(peek,&mut tokens,&self.labels);
+                    if let Ok(either) = some_inst {
+                        match either {
+                            Either::Left(inst) => {
+                                res.push(inst);
+                            }
+                            Either::Right((inst,label)) => {
+                                self.identifier.push((self.number_instructions,label));
+                                res.push(inst);
+                            }
+                        }
+                        
+                    } 
+                }
+                TokenType::GOFZ
+                    | TokenType::GOFNZ
+                    | TokenType::GOFN
+                    | TokenType::GOFNN
+                    | TokenType::GOFC
+                    | TokenType::GOFNC
+                    | TokenType::GOFV
+                    | TokenType::GOFNV => {
+                    let some_inst = Parser::rule_goflag(peek,&mut tokens,&self.labels);
+                    if let Ok(either) = some_inst {
+                        match either {
+                            Either::Left(inst) => {
+                                res.push(inst);
+                            }
+                            Either::Right((inst,label)) => {
+                                self.identifier.push((self.number_instructions,label));
+                                res.push(inst);
+                            }
+                        }
+                        
+                    } 
+                }
                 TokenType::CALL => {
                     let some_inst = Parser::rule_call(&mut tokens,&self.labels);
                     if let Ok(either) = some_inst {
@@ -247,12 +402,150 @@ impl Parser {
                         
                     } 
                 }
+                TokenType::TAILCALL => {
+                    let some_inst = Parser::rule_tailcall(&mut tokens,&self.labels);
+                    if let Ok(either) = some_inst {
+                        match either {
+                            Either::Left(inst) => {
+                                res.push(inst);
+                            }
+                            Either::Right((inst,label)) => {
+                                self.identifier.push((self.number_instructions,label));
+                                res.push(inst);
+                            }
+                        }
+                        
+                    } 
+                }
+                TokenType::SPAWN => {
+                    let some_inst = Parser::rule_spawn(&mut tokens,&self.labels);
+                    if let Ok(either) = some_inst {
+                        match either {
+                            Either::Left(inst) => {
+                                res.push(inst);
+                            }
+                            Either::Right((inst,label)) => {
+                                self.identifier.push((self.number_instructions,label));
+                                res.push(inst);
+                            }
+                        }
+                        
+                    } 
+                }
+                TokenType::SPAWNVM => {
+                    let some_inst = Parser::rule_spawn_vm(&mut tokens,&self.labels);
+                    if let Ok(either) = some_inst {
+                        match either {
+                            Either::Left(inst) => {
+                                res.push(inst);
+                            }
+                            Either::Right((inst,label)) => {
+                                self.identifier.push((self.number_instructions,label));
+                                res.push(inst);
+                            }
+                        }
+                        
+                    } 
+                }
+                TokenType::VECTOR => {
+                    let some_inst = Parser::rule_vector(&mut tokens,&self.labels);
+                    if let Ok(either) = some_inst {
+                        match either {
+                            Either::Left(inst) => {
+                                res.push(inst);
+                            }
+                            Either::Right((inst,label)) => {
+                                self.identifier.push((self.number_instructions,label));
+                                res.push(inst);
+                            }
+                        }
+                        
+                    } 
+                }
+                TokenType::RAISE => {
+                    let some_inst = Parser::rule_uint(&mut tokens).map(Instruction::RAISE);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                }
+                TokenType::TIMER => {
+                    let some_inst = Parser::rule_timer(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                }
+                TokenType::FOPEN => {
+                    let some_inst = Parser::rule_fopen(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                }
+                TokenType::RANDOM => {
+                    res.push(Instruction::RANDOM(Parser::rule_either(&mut tokens).ok()));
+                }
+                TokenType::SEED => {
+                    res.push(Instruction::SEED(Parser::rule_either(&mut tokens).ok()));
+                }
+                TokenType::SLEEP => {
+                    res.push(Instruction::SLEEP(Parser::rule_either(&mut tokens).ok()));
+                }
+                TokenType::ARGV => {
+                    res.push(Instruction::ARGV(Parser::rule_either(&mut tokens).ok()));
+                }
+                TokenType::CLEAR => {
+                    res.push(Instruction::CLEAR(Parser::rule_either(&mut tokens).ok()));
+                }
+                TokenType::IMASK => {
+                    res.push(Instruction::IMASK(Parser::rule_uint(&mut tokens).ok()));
+                }
+                TokenType::IUNMASK => {
+                    res.push(Instruction::IUNMASK(Parser::rule_uint(&mut tokens).ok()));
+                }
+                TokenType::CHANNEW => {
+                    let some_inst = Parser::rule_chan_new(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                }
+                TokenType::YIELD => {
+                    res.push(Instruction::YIELD(Parser::rule_either(&mut tokens).ok()));
+                }
+                TokenType::RESUME => {
+                    res.push(Instruction::RESUME(Parser::rule_either(&mut tokens).ok()));
+                }
+                TokenType::STATUS => {
+                    res.push(Instruction::STATUS(Parser::rule_either(&mut tokens).ok()));
+                }
+                TokenType::PUSHR => {
+                    let some_inst = Parser::rule_regs(&mut tokens).map(Instruction::PUSHR);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                }
+                TokenType::POPR => {
+                    let some_inst = Parser::rule_regs(&mut tokens).map(Instruction::POPR);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                        
+                    } 
+                }
                 TokenType::SCALLP => {
                     let some_inst = Parser::rule_scallp(&mut tokens);
                     if let Ok(inst) = some_inst {
                         res.push(inst);
                     } 
                 }
+                TokenType::RGO => {
+                    let some_inst = Parser::rule_rgo(&mut tokens);
+                    if let Ok(inst) = some_inst {
+                        res.push(inst);
+                    } 
+                }
                 TokenType::RCALL => {
                     let some_inst = Parser::rule_rcall(&mut tokens);
                     if let Ok(inst) = some_inst {
@@ -294,6 +587,12 @@ impl Parser {
                 match res.get_mut(*index) {
                     Some(inst) => {
                         match inst {
+                            Instruction::PUSH(_) => {
+                                *inst = Instruction::PUSH(Either::Left(Word::U64(*addr)));
+                            }
+                            Instruction::RWRITE(_,reg) => {
+                                *inst = Instruction::RWRITE(Word::U64(*addr),*reg);
+                            }
                             Instruction::GO(_) => {
                                 *inst = Instruction::GO(*addr);
                             }
@@ -303,12 +602,59 @@ impl Parser {
                             Instruction::RGOIF(_,reg) => {
                                 *inst = Instruction::RGOIF(*addr,*reg);
                             }
+                            Instruction::GOEQ(_,_,target)
+                                | Instruction::GONE(_,_,target)
+                                | Instruction::GOLT(_,_,target)
+                                | Instruction::GOLE(_,_,target)
+                                | Instruction::GOGT(_,_,target)
+                                | Instruction::GOGE(_,_,target)
+                                | Instruction::GOZ(_,target)
+                                | Instruction::GONZ(_,target)
+                                | Instruction::LOOP(_,target) => {
+                                *target = *addr;
+                            }
+                            Instruction::GOFZ(_) => {
+                                *inst = Instruction::GOFZ(*addr);
+                            }
+                            Instruction::GOFNZ(_) => {
+                                *inst = Instruction::GOFNZ(*addr);
+                            }
+                            Instruction::GOFN(_) => {
+                                *inst = Instruction::GOFN(*addr);
+                            }
+                            Instruction::GOFNN(_) => {
+                                *inst = Instruction::GOFNN(*addr);
+                            }
+                            Instruction::GOFC(_) => {
+                                *inst = Instruction::GOFC(*addr);
+                            }
+                            Instruction::GOFNC(_) => {
+                                *inst = Instruction::GOFNC(*addr);
+                            }
+                            Instruction::GOFV(_) => {
+                                *inst = Instruction::GOFV(*addr);
+                            }
+                            Instruction::GOFNV(_) => {
+                                *inst = Instruction::GOFNV(*addr);
+                            }
                             Instruction::CALL(_) => {
                                 *inst = Instruction::CALL(*addr);
                             }
                             Instruction::CALLP(_,size) => {
                                 *inst = Instruction::CALLP(*addr,*size);
                             }
+                            Instruction::TAILCALL(_,size) => {
+                                *inst = Instruction::TAILCALL(*addr,*size);
+                            }
+                            Instruction::SPAWN(_,size) => {
+                                *inst = Instruction::SPAWN(*addr,*size);
+                            }
+                            Instruction::SPAWNVM(_,size) => {
+                                *inst = Instruction::SPAWNVM(*addr,*size);
+                            }
+                            Instruction::VECTOR(number,_) => {
+                                *inst = Instruction::VECTOR(*number,*addr);
+                            }
                             _ => {
                                 return Err(ParserError::EmptyError);
                             }
@@ -322,6 +668,22 @@ impl Parser {
                 return Err(ParserError::EmptyError);
             }
         }
+        for (index,table,default) in &self.switches {
+            let Some(table) = self.tables.get(table) else {
+                return Err(ParserError::EmptyError);
+            };
+            let mut addrs = Vec::with_capacity(table.len());
+            for label in table {
+                let Some(addr) = self.labels.get(label) else {
+                    return Err(ParserError::EmptyError);
+                };
+                addrs.push(*addr);
+            }
+            let Some(default) = self.labels.get(default) else {
+                return Err(ParserError::EmptyError);
+            };
+            res[*index] = Instruction::SWITCH(addrs,*default);
+        }
 
         return Ok(res);
     }
@@ -379,7 +741,8 @@ impl Parser {
         }
         return Err(ParserError::EmptyError);
     }
-    fn rule_addr_op(tokens : &mut Peekable<Iter<Token>>) -> Result<(Register,isize),ParserError>{
+    // [reg] [reg | imm] or [reg + reg*scale + imm]
+    fn rule_addr_op(tokens : &mut Peekable<Iter<Token>>) -> Result<Address,ParserError>{
         let Some(token) = tokens.peek() else {
             return Err(ParserError::EmptyError);
         };
@@ -388,35 +751,45 @@ impl Parser {
         }else {
             return Err(ParserError::RuleError(token.line, token.column));
         }
-        let reg = Parser::rule_reg(tokens)?;
-        let Some(token) = tokens.peek() else {
-            return Err(ParserError::EmptyError);
+        let mut addr = Address {
+            base : Parser::rule_reg(tokens)?,
+            index : None,
+            displacement : 0,
         };
-        if let TokenType::C_SBR = token.token{
-            tokens.next();
-            return Ok((reg,0));
-        }else if let TokenType::BAR = token.token{
-            tokens.next();
-        }else {
-            return Err(ParserError::RuleError(token.line, token.column));
-        }
-
-        let num = {
-            if let Ok(inum) = Parser::rule_int(tokens) {
-                inum
-            }else {
-                Parser::rule_uint(tokens)? as isize
+        loop {
+            let Some(token) = tokens.next() else {
+                return Err(ParserError::EmptyError);
+            };
+            match token.token {
+                TokenType::C_SBR => {
+                    return Ok(addr);
+                }
+                TokenType::BAR | TokenType::PLUS => {}
+                _ => {
+                    return Err(ParserError::RuleError(token.line, token.column));
+                }
             }
-        };
-        let Some(token) = tokens.peek() else {
-            return Err(ParserError::EmptyError);
-        };
-        if let TokenType::C_SBR = token.token{
-            tokens.next();
-        }else {
-            return Err(ParserError::RuleError(token.line, token.column));
+            if let Ok(reg) = Parser::rule_reg(tokens) {
+                if addr.index.is_some() {
+                    return Err(ParserError::RuleError(token.line, token.column));
+                }
+                let mut scale = 1;
+                if let Some(Token { token : TokenType::STAR, .. }) = tokens.peek() {
+                    tokens.next();
+                    scale = Parser::rule_uint(tokens)?;
+                }
+                addr.index = Some((reg,scale));
+                continue;
+            }
+            let num = {
+                if let Ok(inum) = Parser::rule_int(tokens) {
+                    inum
+                }else {
+                    Parser::rule_uint(tokens)? as isize
+                }
+            };
+            addr.displacement += num;
         }
-        return Ok((reg,num));
     }
     fn rule_word(tokens : &mut Peekable<Iter<Token>>) -> Result<Word,ParserError>{
         if let Ok(num) = Parser::rule_bool(tokens){
@@ -441,21 +814,17 @@ impl Parser {
     fn rule_reg(tokens : &mut Peekable<Iter<Token>>) -> Result<Register,ParserError>{
         if let Some(token) = tokens.peek() {
             match token.token {
-                TokenType::R1 => {
+                TokenType::R(idx) => {
                     tokens.next();
-                    return Ok(Register::R1);
+                    return Ok(Register::R(idx));
                 }
-                TokenType::R2 => {
+                TokenType::Ts => {
                     tokens.next();
-                    return Ok(Register::R2);
+                    return Ok(Register::Ts);
                 }
-                TokenType::R3 => {
+                TokenType::Bs => {
                     tokens.next();
-                    return Ok(Register::R3);
-                }
-                TokenType::R4 => {
-                    tokens.next();
-                    return Ok(Register::R3);
+                    return Ok(Register::Bs);
                 }
                 TokenType::He => {
                     tokens.next();
@@ -486,6 +855,126 @@ impl Parser {
             TokenType::POP => {
                 return Ok(Instruction::POP);
             }
+            TokenType::DUP => {
+                return Ok(Instruction::DUP);
+            }
+            TokenType::MEMSIZE => {
+                return Ok(Instruction::MEMSIZE);
+            }
+            TokenType::VNEW => {
+                return Ok(Instruction::VNEW);
+            }
+            TokenType::VPUSH => {
+                return Ok(Instruction::VPUSH);
+            }
+            TokenType::VPOP => {
+                return Ok(Instruction::VPOP);
+            }
+            TokenType::VGET => {
+                return Ok(Instruction::VGET);
+            }
+            TokenType::VSET => {
+                return Ok(Instruction::VSET);
+            }
+            TokenType::VLEN => {
+                return Ok(Instruction::VLEN);
+            }
+            TokenType::MNEW => {
+                return Ok(Instruction::MNEW);
+            }
+            TokenType::MSET => {
+                return Ok(Instruction::MSET);
+            }
+            TokenType::MGET => {
+                return Ok(Instruction::MGET);
+            }
+            TokenType::MHAS => {
+                return Ok(Instruction::MHAS);
+            }
+            TokenType::MDEL => {
+                return Ok(Instruction::MDEL);
+            }
+            TokenType::MLEN => {
+                return Ok(Instruction::MLEN);
+            }
+            TokenType::OFREE => {
+                return Ok(Instruction::OFREE);
+            }
+            TokenType::SEND => {
+                return Ok(Instruction::SEND);
+            }
+            TokenType::IRET => {
+                return Ok(Instruction::IRET);
+            }
+            TokenType::PIXEL => {
+                return Ok(Instruction::PIXEL);
+            }
+            TokenType::PRESENT => {
+                return Ok(Instruction::PRESENT);
+            }
+            TokenType::FREAD => {
+                return Ok(Instruction::FREAD);
+            }
+            TokenType::FWRITE => {
+                return Ok(Instruction::FWRITE);
+            }
+            TokenType::FSEEK => {
+                return Ok(Instruction::FSEEK);
+            }
+            TokenType::FCLOSE => {
+                return Ok(Instruction::FCLOSE);
+            }
+            TokenType::LISTEN => {
+                return Ok(Instruction::LISTEN);
+            }
+            TokenType::CONNECT => {
+                return Ok(Instruction::CONNECT);
+            }
+            TokenType::ACCEPT => {
+                return Ok(Instruction::ACCEPT);
+            }
+            TokenType::SOCKSEND => {
+                return Ok(Instruction::SOCKSEND);
+            }
+            TokenType::SOCKRECV => {
+                return Ok(Instruction::SOCKRECV);
+            }
+            TokenType::SOCKPORT => {
+                return Ok(Instruction::SOCKPORT);
+            }
+            TokenType::SOCKCLOSE => {
+                return Ok(Instruction::SOCKCLOSE);
+            }
+            TokenType::ARGC => {
+                return Ok(Instruction::ARGC);
+            }
+            TokenType::GETENV => {
+                return Ok(Instruction::GETENV);
+            }
+            TokenType::RANDF => {
+                return Ok(Instruction::RANDF);
+            }
+            TokenType::CLOCK => {
+                return Ok(Instruction::CLOCK);
+            }
+            TokenType::TRYSEND => {
+                return Ok(Instruction::TRYSEND);
+            }
+            TokenType::RECV => {
+                return Ok(Instruction::RECV);
+            }
+            TokenType::TRYRECV => {
+                return Ok(Instruction::TRYRECV);
+            }
+            TokenType::SWAP => {
+                return Ok(Instruction::SWAP);
+            }
+            TokenType::OVER => {
+                return Ok(Instruction::OVER);
+            }
+            TokenType::ROT => {
+                return Ok(Instruction::ROT);
+            }
             /* OPERATION */
             TokenType::ADD => {
                 return Ok(Instruction::ADD);
@@ -545,6 +1034,78 @@ impl Parser {
             TokenType::NOT => {
                 return Ok(Instruction::NOT);
             }
+            TokenType::ROTL => {
+                return Ok(Instruction::ROTL);
+            }
+            TokenType::ROTR => {
+                return Ok(Instruction::ROTR);
+            }
+            TokenType::ASHR => {
+                return Ok(Instruction::ASHR);
+            }
+            TokenType::POPCNT => {
+                return Ok(Instruction::POPCNT);
+            }
+            TokenType::CLZ => {
+                return Ok(Instruction::CLZ);
+            }
+            TokenType::CTZ => {
+                return Ok(Instruction::CTZ);
+            }
+            TokenType::NEG => {
+                return Ok(Instruction::NEG);
+            }
+            TokenType::BNOT => {
+                return Ok(Instruction::BNOT);
+            }
+            TokenType::SQRT => {
+                return Ok(Instruction::SQRT);
+            }
+            TokenType::POW => {
+                return Ok(Instruction::POW);
+            }
+            TokenType::EXP => {
+                return Ok(Instruction::EXP);
+            }
+            TokenType::LN => {
+                return Ok(Instruction::LN);
+            }
+            TokenType::SIN => {
+                return Ok(Instruction::SIN);
+            }
+            TokenType::COS => {
+                return Ok(Instruction::COS);
+            }
+            TokenType::TAN => {
+                return Ok(Instruction::TAN);
+            }
+            TokenType::ATAN2 => {
+                return Ok(Instruction::ATAN2);
+            }
+            TokenType::FLOOR => {
+                return Ok(Instruction::FLOOR);
+            }
+            TokenType::CEIL => {
+                return Ok(Instruction::CEIL);
+            }
+            TokenType::ROUND => {
+                return Ok(Instruction::ROUND);
+            }
+            TokenType::ABS => {
+                return Ok(Instruction::ABS);
+            }
+            TokenType::MIN => {
+                return Ok(Instruction::MIN);
+            }
+            TokenType::MAX => {
+                return Ok(Instruction::MAX);
+            }
+            TokenType::ISNAN => {
+                return Ok(Instruction::ISNAN);
+            }
+            TokenType::ISINF => {
+                return Ok(Instruction::ISINF);
+            }
             TokenType::F2I => {
                 return Ok(Instruction::F2I);
             }            
@@ -615,6 +1176,9 @@ impl Parser {
             TokenType::NOP => {
                 return Ok(Instruction::NOP);
             }
+            TokenType::SGO => {
+                return Ok(Instruction::SGO);
+            }
             TokenType::SCALL => {
                 return Ok(Instruction::SCALL);
             }
@@ -628,88 +1192,156 @@ impl Parser {
         match token.token {
             /* OPERATION */
             TokenType::RNOT => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RNOT(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RNOT(x)));
             }            
+            TokenType::RPOPCNT => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RPOPCNT(x)));
+            }
+            TokenType::RCLZ => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RCLZ(x)));
+            }
+            TokenType::RCTZ => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RCTZ(x)));
+            }
+            TokenType::RNEG => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RNEG(x)));
+            }
+            TokenType::RBNOT => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RBNOT(x)));
+            }
+            TokenType::RSQRT => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RSQRT(x)));
+            }
+            TokenType::REXP => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::REXP(x)));
+            }
+            TokenType::RLN => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RLN(x)));
+            }
+            TokenType::RSIN => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RSIN(x)));
+            }
+            TokenType::RCOS => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RCOS(x)));
+            }
+            TokenType::RTAN => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RTAN(x)));
+            }
+            TokenType::RFLOOR => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RFLOOR(x)));
+            }
+            TokenType::RCEIL => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RCEIL(x)));
+            }
+            TokenType::RROUND => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RROUND(x)));
+            }
+            TokenType::RABS => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RABS(x)));
+            }
+            TokenType::RISNAN => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RISNAN(x)));
+            }
+            TokenType::RISINF => {
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RISINF(x)));
+            }
             TokenType::RF2I => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RF2I(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RF2I(x)));
             }            
             TokenType::RF2U => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RF2U(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RF2U(x)));
             }            
             TokenType::RF2B => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RF2B(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RF2B(x)));
             }
             TokenType::RF2C => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RF2C(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RF2C(x)));
             }
             TokenType::RI2F => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RI2F(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RI2F(x)));
             }
             TokenType::RI2U => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RI2U(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RI2U(x)));
             }
             TokenType::RI2B => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RI2B(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RI2B(x)));
             }
             TokenType::RI2C => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RI2C(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RI2C(x)));
             }
             TokenType::RU2I => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RU2I(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RU2I(x)));
             }
             TokenType::RU2F => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RU2F(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RU2F(x)));
             }
             TokenType::RU2C => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RU2C(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RU2C(x)));
             }
             TokenType::RU2B => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RU2B(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RU2B(x)));
             }
             TokenType::RC2I => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RC2I(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RC2I(x)));
             }
             TokenType::RC2F => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RC2F(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RC2F(x)));
             }
             TokenType::RC2U => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RC2U(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RC2U(x)));
             }
             TokenType::RC2B => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RC2B(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RC2B(x)));
             }
             TokenType::RB2I => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RB2I(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RB2I(x)));
             }
             TokenType::RB2F => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RB2F(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RB2F(x)));
             }
             TokenType::RB2U => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RB2U(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RB2U(x)));
             }
             TokenType::RB2C => {
-                let x = Parser::rule_either(tokens)?;
-                return Ok(Instruction::RB2C(x));
+                let (dest,x) = Parser::dest_unary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RB2C(x)));
             }
             TokenType::RDMP => {
                 let x = Parser::rule_either(tokens)?;
@@ -720,6 +1352,33 @@ impl Parser {
             }
         }
     }
+    // R-forms take an optional destination register before their operands
+    fn with_dest(dest : Option<Register>,inst : Instruction) -> Instruction{
+        match dest {
+            Some(reg) => Instruction::RDEST(reg,Box::new(inst)),
+            None => inst,
+        }
+    }
+    fn dest_unary_either_param(tokens : &mut Peekable<Iter<Token>>) -> Result<(Option<Register>,Either<Word,Register>),ParserError>{
+        let x = Parser::rule_either(tokens)?;
+        if let Ok(y) = Parser::rule_either(tokens) {
+            let Either::Right(dest) = x else {
+                return Err(ParserError::EmptyError);
+            };
+            return Ok((Some(dest),y));
+        }
+        return Ok((None,x));
+    }
+    fn dest_binary_either_param(tokens : &mut Peekable<Iter<Token>>) -> Result<(Option<Register>,Either<Word,Register>,Either<Word,Register>),ParserError>{
+        let (x,y) = Parser::binary_either_param(tokens)?;
+        if let Ok(z) = Parser::rule_either(tokens) {
+            let Either::Right(dest) = x else {
+                return Err(ParserError::EmptyError);
+            };
+            return Ok((Some(dest),y,z));
+        }
+        return Ok((None,x,y));
+    }
     fn binary_either_param(tokens : &mut Peekable<Iter<Token>>) -> Result<(Either<Word,Register>,Either<Word,Register>),ParserError>{
         let x = Parser::rule_either(tokens)?;
         let y = Parser::rule_either(tokens)?;
@@ -729,77 +1388,105 @@ impl Parser {
         match token.token {
             /* OPERATION */
             TokenType::RADD => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RADD(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RADD(x,y)));
             }
             TokenType::RMINUS => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RMINUS(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RMINUS(x,y)));
             }
             TokenType::RMUL => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RMUL(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RMUL(x,y)));
             }
             TokenType::RDIV => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RDIV(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RDIV(x,y)));
             }
             TokenType::RMOD => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RMOD(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RMOD(x,y)));
             }
 
             TokenType::RBAND => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RBAND(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RBAND(x,y)));
             }
             TokenType::RBOR => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RBOR(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RBOR(x,y)));
             }
             TokenType::RBXOR => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RBXOR(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RBXOR(x,y)));
             }
             TokenType::RRSHIFT => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RRSHIFT(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RRSHIFT(x,y)));
             }
             TokenType::RLSHIFT => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RLSHIFT(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RLSHIFT(x,y)));
+            }
+            TokenType::RROTL => {
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RROTL(x,y)));
+            }
+            TokenType::RROTR => {
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RROTR(x,y)));
+            }
+            TokenType::RASHR => {
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RASHR(x,y)));
+            }
+            TokenType::RPOW => {
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RPOW(x,y)));
+            }
+            TokenType::RATAN2 => {
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RATAN2(x,y)));
+            }
+            TokenType::RMIN => {
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RMIN(x,y)));
+            }
+            TokenType::RMAX => {
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RMAX(x,y)));
             }
             TokenType::RLESS => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RLESS(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RLESS(x,y)));
             }
             TokenType::RELESS => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RELESS(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RELESS(x,y)));
             }
             TokenType::RGREAT => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RGREAT(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RGREAT(x,y)));
             }
             TokenType::REGREAT => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::REGREAT(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::REGREAT(x,y)));
             }
             TokenType::REQUAL => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::REQUAL(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::REQUAL(x,y)));
             }
             TokenType::RDIFF => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RDIFF(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RDIFF(x,y)));
             }
             TokenType::RAND => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::RAND(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::RAND(x,y)));
             }
             TokenType::ROR => {
-                let (x,y) = Parser::binary_either_param( tokens)?;
-                return Ok(Instruction::ROR(x,y));
+                let (dest,x,y) = Parser::dest_binary_either_param(tokens)?;
+                return Ok(Parser::with_dest(dest,Instruction::ROR(x,y)));
             }
             _ => {
                 return Err(ParserError::RuleError(token.line, token.column));
@@ -808,9 +1495,28 @@ impl Parser {
 
     }
 
-    fn rule_push(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+    fn rule_push(tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
+        if let Ok(label) = Parser::rule_label(tokens) {
+            if let Some(addr) = labels.get(&label) {
+                return Ok(Either::Left(Instruction::PUSH(Either::Left(Word::U64(*addr)))));
+            }else {
+                return Ok(Either::Right((Instruction::PUSH(Either::Left(Word::U64(0))),label)))
+            }
+        }
         let word = Parser::rule_either(tokens)?;
-        return Ok(Instruction::PUSH(word));
+        return Ok(Either::Left(Instruction::PUSH(word)));
+    }
+    fn rule_pick(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let n = Parser::rule_uint(tokens)?;
+        return Ok(Instruction::PICK(n));
+    }
+    fn rule_roll(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let n = Parser::rule_uint(tokens)?;
+        return Ok(Instruction::ROLL(n));
+    }
+    fn rule_dropn(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let n = Parser::rule_uint(tokens)?;
+        return Ok(Instruction::DROPN(n));
     }
     fn rule_scopy(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
         let reg = Parser::rule_reg(tokens)?;
@@ -830,10 +1536,18 @@ impl Parser {
         let reg2 = Parser::rule_reg(tokens)?;
         return Ok(Instruction::RMOVE(reg1,reg2));
     }
-    fn rule_rwrite(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+    fn rule_rwrite(tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
+        if let Ok(label) = Parser::rule_label(tokens) {
+            let reg = Parser::rule_reg(tokens)?;
+            if let Some(addr) = labels.get(&label) {
+                return Ok(Either::Left(Instruction::RWRITE(Word::U64(*addr),reg)));
+            }else {
+                return Ok(Either::Right((Instruction::RWRITE(Word::U64(0),reg),label)))
+            }
+        }
         let word = Parser::rule_word(tokens)?;
         let reg = Parser::rule_reg(tokens)?;
-        return Ok(Instruction::RWRITE(word,reg));
+        return Ok(Either::Left(Instruction::RWRITE(word,reg)));
     }
     fn rule_load(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
         let addr_op = Parser::rule_addr_op(tokens)?;
@@ -867,9 +1581,9 @@ impl Parser {
         return Ok(Instruction::SREADD(size,offset));
     }
     fn rule_write(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
-        let word = Parser::rule_word(tokens)?;
+        let value = Parser::rule_either(tokens).ok();
         let addr_op = Parser::rule_addr_op(tokens)?;
-        return Ok(Instruction::WRITE(word,addr_op));
+        return Ok(Instruction::WRITE(value,addr_op));
     }
     fn rule_swrite(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
         return Ok(Instruction::SWRITE);
@@ -885,6 +1599,62 @@ impl Parser {
     fn rule_sfree(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
         return Ok(Instruction::SFREE);
     }
+    fn rule_copy(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let dst = Parser::rule_addr_op(tokens)?;
+        let src = Parser::rule_addr_op(tokens)?;
+        let count = Parser::rule_either(tokens)?;
+        return Ok(Instruction::COPY(dst,src,count));
+    }
+    fn rule_fill(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let dst = Parser::rule_addr_op(tokens)?;
+        let (value,count) = Parser::binary_either_param(tokens)?;
+        return Ok(Instruction::FILL(dst,value,count));
+    }
+    fn rule_compare(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let lhs = Parser::rule_addr_op(tokens)?;
+        let rhs = Parser::rule_addr_op(tokens)?;
+        let count = Parser::rule_either(tokens)?;
+        return Ok(Instruction::COMPARE(lhs,rhs,count));
+    }
+    fn rule_linear_load(token : &Token,tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let addr_op = Parser::rule_addr_op(tokens)?;
+        match token.token {
+            TokenType::LOAD8U => return Ok(Instruction::LOAD8(addr_op,false)),
+            TokenType::LOAD8I => return Ok(Instruction::LOAD8(addr_op,true)),
+            TokenType::LOAD16U => return Ok(Instruction::LOAD16(addr_op,false)),
+            TokenType::LOAD16I => return Ok(Instruction::LOAD16(addr_op,true)),
+            TokenType::LOAD32U => return Ok(Instruction::LOAD32(addr_op,false)),
+            TokenType::LOAD32I => return Ok(Instruction::LOAD32(addr_op,true)),
+            TokenType::LOAD64U => return Ok(Instruction::LOAD64(addr_op,false)),
+            TokenType::LOAD64I => return Ok(Instruction::LOAD64(addr_op,true)),
+            _ => return Err(ParserError::RuleError(token.line, token.column))
+        }
+    }
+    fn rule_linear_store(token : &Token,tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let value = Parser::rule_either(tokens).ok();
+        let addr_op = Parser::rule_addr_op(tokens)?;
+        match token.token {
+            TokenType::STORE8 => return Ok(Instruction::STORE8(value,addr_op)),
+            TokenType::STORE16 => return Ok(Instruction::STORE16(value,addr_op)),
+            TokenType::STORE32 => return Ok(Instruction::STORE32(value,addr_op)),
+            TokenType::STORE64 => return Ok(Instruction::STORE64(value,addr_op)),
+            _ => return Err(ParserError::RuleError(token.line, token.column))
+        }
+    }
+    fn rule_memgrow(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let size = Parser::rule_uint(tokens)?;
+        return Ok(Instruction::MEMGROW(size));
+    }
+    fn rule_endian(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let Some(token) = tokens.next() else {
+            return Err(ParserError::EmptyError);
+        };
+        match token.token {
+            TokenType::LITTLE => return Ok(Instruction::ENDIAN(Endian::Little)),
+            TokenType::BIG => return Ok(Instruction::ENDIAN(Endian::Big)),
+            _ => return Err(ParserError::RuleError(token.line, token.column))
+        }
+    }
     fn rule_either(tokens : &mut Peekable<Iter<Token>>) -> Result<Either<Word,Register>,ParserError> {
         let reg = Parser::rule_reg(tokens);
         if reg.is_err() {
@@ -912,6 +1682,32 @@ impl Parser {
             return Ok(Either::Right((Instruction::GO(0),label.to_owned())))
         }
     }
+    // .table %name [ %label ... ]
+    fn rule_table(tokens : &mut Peekable<Iter<Token>>) -> Result<(String,Vec<String>),ParserError>{
+        let name = Parser::rule_label(tokens)?;
+        let Some(token) = tokens.next() else {
+            return Err(ParserError::EmptyError);
+        };
+        let TokenType::O_SBR = token.token else {
+            return Err(ParserError::RuleError(token.line, token.column));
+        };
+        let mut table = Vec::new();
+        loop {
+            let Some(token) = tokens.peek() else {
+                return Err(ParserError::EmptyError);
+            };
+            if let TokenType::C_SBR = token.token {
+                tokens.next();
+                return Ok((name,table));
+            }
+            table.push(Parser::rule_label(tokens)?);
+        }
+    }
+    fn rule_switch(tokens : &mut Peekable<Iter<Token>>) -> Result<(String,String),ParserError>{
+        let table = Parser::rule_label(tokens)?;
+        let default = Parser::rule_label(tokens)?;
+        return Ok((table,default));
+    }
     fn rule_goif(tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
         let label = Parser::rule_label(tokens)?;
         if let Some(addr) = labels.get(&label) {
@@ -929,6 +1725,81 @@ impl Parser {
             return Ok(Either::Right((Instruction::RGOIF(0,reg),label.to_owned())))
         }
     }
+    // compare and branch : the operands come before the label
+    fn rule_gocmp(token : &Token,tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
+        let mut inst = match token.token {
+            TokenType::GOEQ => {
+                let (x,y) = Parser::binary_either_param(tokens)?;
+                Instruction::GOEQ(x,y,0)
+            }
+            TokenType::GONE => {
+                let (x,y) = Parser::binary_either_param(tokens)?;
+                Instruction::GONE(x,y,0)
+            }
+            TokenType::GOLT => {
+                let (x,y) = Parser::binary_either_param(tokens)?;
+                Instruction::GOLT(x,y,0)
+            }
+            TokenType::GOLE => {
+                let (x,y) = Parser::binary_either_param(tokens)?;
+                Instruction::GOLE(x,y,0)
+            }
+            TokenType::GOGT => {
+                let (x,y) = Parser::binary_either_param(tokens)?;
+                Instruction::GOGT(x,y,0)
+            }
+            TokenType::GOGE => {
+                let (x,y) = Parser::binary_either_param(tokens)?;
+                Instruction::GOGE(x,y,0)
+            }
+            TokenType::GOZ => Instruction::GOZ(Parser::rule_either(tokens)?,0),
+            TokenType::GONZ => Instruction::GONZ(Parser::rule_either(tokens)?,0),
+            TokenType::LOOP => Instruction::LOOP(Parser::rule_reg(tokens)?,0),
+            _ => return Err(ParserError::RuleError(token.line, token.column))
+        };
+        let label = Parser::rule_label(tokens)?;
+        let Some(addr) = labels.get(&label) else {
+            return Ok(Either::Right((inst,label)));
+        };
+        match &mut inst {
+            Instruction::GOEQ(_,_,target)
+            | Instruction::GONE(_,_,target)
+            | Instruction::GOLT(_,_,target)
+            | Instruction::GOLE(_,_,target)
+            | Instruction::GOGT(_,_,target)
+            | Instruction::GOGE(_,_,target)
+            | Instruction::GOZ(_,target)
+            | Instruction::GONZ(_,target)
+            | Instruction::LOOP(_,target) => {
+                *target = *addr;
+            }
+            _ => {}
+        }
+        return Ok(Either::Left(inst));
+    }
+    fn rule_goflag(token : &Token,tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
+        let label = Parser::rule_label(tokens)?;
+        let addr = labels.get(&label).copied();
+        let inst = {
+            let addr = addr.unwrap_or(0);
+            match token.token {
+                TokenType::GOFZ => Instruction::GOFZ(addr),
+                TokenType::GOFNZ => Instruction::GOFNZ(addr),
+                TokenType::GOFN => Instruction::GOFN(addr),
+                TokenType::GOFNN => Instruction::GOFNN(addr),
+                TokenType::GOFC => Instruction::GOFC(addr),
+                TokenType::GOFNC => Instruction::GOFNC(addr),
+                TokenType::GOFV => Instruction::GOFV(addr),
+                TokenType::GOFNV => Instruction::GOFNV(addr),
+                _ => return Err(ParserError::RuleError(token.line, token.column))
+            }
+        };
+        if addr.is_some() {
+            return Ok(Either::Left(inst));
+        }else {
+            return Ok(Either::Right((inst,label)))
+        }
+    }
     fn rule_call(tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
         let label = Parser::rule_label(tokens)?;
         if let Some(addr) = labels.get(&label) {
@@ -946,6 +1817,74 @@ impl Parser {
             return Ok(Either::Right((Instruction::CALLP(0,size),label.to_owned())))
         }
     }
+    fn rule_tailcall(tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
+        let label = Parser::rule_label(tokens)?;
+        let size = Parser::rule_uint(tokens)?;
+        if let Some(addr) = labels.get(&label) {
+            return Ok(Either::Left(Instruction::TAILCALL(*addr,size)));
+        }else {
+            return Ok(Either::Right((Instruction::TAILCALL(0,size),label.to_owned())))
+        }
+    }
+    // the number of words moved to the coroutine stack defaults to 0
+    fn rule_spawn(tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
+        let label = Parser::rule_label(tokens)?;
+        let size = Parser::rule_uint(tokens).unwrap_or(0);
+        if let Some(addr) = labels.get(&label) {
+            return Ok(Either::Left(Instruction::SPAWN(*addr,size)));
+        }else {
+            return Ok(Either::Right((Instruction::SPAWN(0,size),label.to_owned())))
+        }
+    }
+    // the number of words moved to the new vm stack defaults to 0
+    fn rule_spawn_vm(tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
+        let label = Parser::rule_label(tokens)?;
+        let size = Parser::rule_uint(tokens).unwrap_or(0);
+        if let Some(addr) = labels.get(&label) {
+            return Ok(Either::Left(Instruction::SPAWNVM(*addr,size)));
+        }else {
+            return Ok(Either::Right((Instruction::SPAWNVM(0,size),label.to_owned())))
+        }
+    }
+    fn rule_vector(tokens : &mut Peekable<Iter<Token>>,labels : &HashMap<String,usize>) -> Result<Either<Instruction,(Instruction,String)>,ParserError>{
+        let number = Parser::rule_uint(tokens)?;
+        let label = Parser::rule_label(tokens)?;
+        if let Some(addr) = labels.get(&label) {
+            return Ok(Either::Left(Instruction::VECTOR(number,*addr)));
+        }else {
+            return Ok(Either::Right((Instruction::VECTOR(number,0),label.to_owned())))
+        }
+    }
+    // 0 read, 1 write, 2 append, 3 read and write
+    fn rule_fopen(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let Some(token) = tokens.peek() else {
+            return Err(ParserError::EmptyError);
+        };
+        let (line,column) = (token.line,token.column);
+        let code = Parser::rule_uint(tokens)?;
+        let Some(mode) = FileMode::from_code(code) else {
+            return Err(ParserError::RuleError(line, column));
+        };
+        return Ok(Instruction::FOPEN(mode));
+    }
+    fn rule_timer(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let period = Parser::rule_uint(tokens)?;
+        let number = Parser::rule_uint(tokens)?;
+        return Ok(Instruction::TIMER(period,number));
+    }
+    // the capacity defaults to 0 : unbounded
+    fn rule_chan_new(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let capacity = Parser::rule_uint(tokens).unwrap_or(0);
+        return Ok(Instruction::CHANNEW(capacity));
+    }
+    // one or more registers
+    fn rule_regs(tokens : &mut Peekable<Iter<Token>>) -> Result<Vec<Register>,ParserError>{
+        let mut registers = vec![Parser::rule_reg(tokens)?];
+        while let Ok(reg) = Parser::rule_reg(tokens) {
+            registers.push(reg);
+        }
+        return Ok(registers);
+    }
     fn rule_scallp(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
         let size = Parser::rule_uint(tokens)?;
         return Ok(Instruction::SCALLP(size));
@@ -954,6 +1893,10 @@ impl Parser {
         let size = Parser::rule_uint(tokens)?;
         return Ok(Instruction::RET(size));
     }
+    fn rule_rgo(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
+        let reg = Parser::rule_reg(tokens)?;
+        return Ok(Instruction::RGO(reg));
+    }
     fn rule_rcall(tokens : &mut Peekable<Iter<Token>>) -> Result<Instruction,ParserError>{
         let reg = Parser::rule_reg(tokens)?;
         return Ok(Instruction::RCALL(reg));
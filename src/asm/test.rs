@@ -1,5 +1,5 @@
 use std::{fs, path::{PathBuf}};
-use crate::asm::{parser::Parser, lexer};
+use crate::asm::{parser::Parser, lexer, token::TokenType};
 
 use super::{asm::Vasm};
 
@@ -44,3 +44,11 @@ fn test_asm_compile() {
 fn test_asm_decompile() {
     Vasm::build(test_file("test.vasm")).unwrap().dissamble();
 }
+
+#[test]
+fn test_lexer_unicode_char() {
+    let res = lexer::tokenize("push 'é' push '\\u{1F600}'").unwrap();
+    assert!(matches!(res[1].token, TokenType::CHAR('é')));
+    assert!(matches!(res[3].token, TokenType::CHAR('\u{1F600}')));
+    assert!(lexer::tokenize("push '\\u{D800}'").is_err());
+}
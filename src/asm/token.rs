@@ -1,3 +1,4 @@
+use crate::vm::register::GENERAL_REGISTERS;
 
 
 #[derive(Debug)]
@@ -18,12 +19,44 @@ pub enum TokenType {
     ALLOC,
     FREE,
     SFREE,
+    COPY,
+    FILL,
+    COMPARE,
+    /* LINEAR MEMORY */
+    LOAD8U,
+    LOAD8I,
+    LOAD16U,
+    LOAD16I,
+    LOAD32U,
+    LOAD32I,
+    LOAD64U,
+    LOAD64I,
+    STORE8,
+    STORE16,
+    STORE32,
+    STORE64,
+    MEMSIZE,
+    MEMGROW,
+    ENDIAN,
+    LITTLE,
+    BIG,
+    /* OBJECTS */
+    VNEW,
+    VPUSH,
+    VPOP,
+    VGET,
+    VSET,
+    VLEN,
+    MNEW,
+    MSET,
+    MGET,
+    MHAS,
+    MDEL,
+    MLEN,
+    OFREE,
 
     /* REGISTER */
-    R1,
-    R2,
-    R3,
-    R4,
+    R(u8),
     Ts,
     Bs,
     He,
@@ -33,6 +66,13 @@ pub enum TokenType {
     /* MEMORY ACCESS */
     PUSH,
     POP,
+    DUP,
+    SWAP,
+    OVER,
+    ROT,
+    PICK,
+    ROLL,
+    DROPN,
     SCOPY,
     SMOVE,
     RCOPY,
@@ -73,6 +113,22 @@ pub enum TokenType {
     // <<
     LSHIFT,
     RLSHIFT,
+    ASHR,
+    RASHR,
+    ROTL,
+    RROTL,
+    ROTR,
+    RROTR,
+    BNOT,
+    RBNOT,
+    POPCNT,
+    RPOPCNT,
+    CLZ,
+    RCLZ,
+    CTZ,
+    RCTZ,
+    NEG,
+    RNEG,
 
 
     // == 
@@ -104,6 +160,40 @@ pub enum TokenType {
     EGREAT,
     REGREAT,
 
+    /* FLOAT MATH */
+    SQRT,
+    RSQRT,
+    POW,
+    RPOW,
+    EXP,
+    REXP,
+    LN,
+    RLN,
+    SIN,
+    RSIN,
+    COS,
+    RCOS,
+    TAN,
+    RTAN,
+    ATAN2,
+    RATAN2,
+    FLOOR,
+    RFLOOR,
+    CEIL,
+    RCEIL,
+    ROUND,
+    RROUND,
+    ABS,
+    RABS,
+    MIN,
+    RMIN,
+    MAX,
+    RMAX,
+    ISNAN,
+    RISNAN,
+    ISINF,
+    RISINF,
+
     F2I,
     F2U,
     F2B,
@@ -157,8 +247,29 @@ pub enum TokenType {
     NOP,
     LABEL(String),
     GO,
+    RGO,
+    SGO,
+    TABLE,
+    SWITCH,
     GOIF,
     RGOIF,
+    GOEQ,
+    GONE,
+    GOLT,
+    GOLE,
+    GOGT,
+    GOGE,
+    GOZ,
+    GONZ,
+    LOOP,
+    GOFZ,
+    GOFNZ,
+    GOFN,
+    GOFNN,
+    GOFC,
+    GOFNC,
+    GOFV,
+    GOFNV,
     CALL,
     RET,
     SCALL,
@@ -166,19 +277,70 @@ pub enum TokenType {
     SCALLP,
     RCALL,
     RCALLP,
+    TAILCALL,
+    PUSHR,
+    POPR,
+    SPAWN,
+    YIELD,
+    RESUME,
+    STATUS,
+    CHANNEW,
+    SEND,
+    TRYSEND,
+    RECV,
+    TRYRECV,
+    SPAWNVM,
+    VECTOR,
+    RAISE,
+    IRET,
+    TIMER,
+    IMASK,
+    IUNMASK,
+    PIXEL,
+    CLEAR,
+    PRESENT,
+    FOPEN,
+    FREAD,
+    FWRITE,
+    FSEEK,
+    FCLOSE,
+    LISTEN,
+    CONNECT,
+    ACCEPT,
+    SOCKSEND,
+    SOCKRECV,
+    SOCKPORT,
+    SOCKCLOSE,
+    ARGC,
+    ARGV,
+    GETENV,
+    RANDOM,
+    RANDF,
+    SEED,
+    CLOCK,
+    SLEEP,
     /* OTHER */
     O_SBR,
     C_SBR,
-    BAR
+    BAR,
+    PLUS,
+    STAR
 }
 
 fn register(word:&str) -> Option<TokenType> {
-    return match word.to_ascii_lowercase().as_str() {
+    let lower = word.to_ascii_lowercase();
+    if let Some(idx) = lower.strip_prefix('r') {
+        // r0 to r31, without sign or leading zero
+        if idx.chars().all(|c| c.is_ascii_digit()) && (idx == "0" || !idx.starts_with('0')) {
+            if let Ok(idx) = idx.parse::<u8>() {
+                if (idx as usize) < GENERAL_REGISTERS {
+                    return Some(TokenType::R(idx));
+                }
+            }
+        }
+    }
+    return match lower.as_str() {
         /* REGISTER */
-        "r1" => Some(TokenType::R1),
-        "r2" => Some(TokenType::R2),
-        "r3" => Some(TokenType::R3),
-        "r4" => Some(TokenType::R4),
         "ts" => Some(TokenType::Ts),
         "bs" => Some(TokenType::Bs),
         "he" => Some(TokenType::He),
@@ -239,6 +401,16 @@ pub fn match_token_type(src : &str) -> Option<TokenType> {
 
                     return Some(TokenType::CHAR(code as char));
                 }
+                'u' => {
+                    let Some(code) = src[3..src.len()-1].strip_prefix('{')
+                        .and_then(|code| code.strip_suffix('}')) else {
+                        return None
+                    };
+                    let Ok(code) = u32::from_str_radix(code,16) else {
+                        return None
+                    };
+                    return char::from_u32(code).map(TokenType::CHAR);
+                }
                 _ => return None
             }
         }
@@ -255,12 +427,21 @@ pub fn match_token_type(src : &str) -> Option<TokenType> {
         "|" => Some(TokenType::BAR),
         "[" => Some(TokenType::O_SBR),
         "]" => Some(TokenType::C_SBR),
+        "+" => Some(TokenType::PLUS),
+        "*" => Some(TokenType::STAR),
         "true" => Some(TokenType::TRUE),
         "false" => Some(TokenType::FALSE),
         /* MEMORY ACCESS */
         "rwrite" => Some(TokenType::RWRITE),
         "push" => Some(TokenType::PUSH),
         "pop" => Some(TokenType::POP),
+        "dup" => Some(TokenType::DUP),
+        "swap" => Some(TokenType::SWAP),
+        "over" => Some(TokenType::OVER),
+        "rot" => Some(TokenType::ROT),
+        "pick" => Some(TokenType::PICK),
+        "roll" => Some(TokenType::ROLL),
+        "dropn" => Some(TokenType::DROPN),
         "scopy" => Some(TokenType::SCOPY),
         "smove" => Some(TokenType::SMOVE),
         "rcopy" => Some(TokenType::RCOPY),
@@ -276,6 +457,41 @@ pub fn match_token_type(src : &str) -> Option<TokenType> {
         "alloc" => Some(TokenType::ALLOC),
         "free" => Some(TokenType::FREE),
         "sfree" => Some(TokenType::SFREE),
+        "copy" => Some(TokenType::COPY),
+        "fill" => Some(TokenType::FILL),
+        "compare" => Some(TokenType::COMPARE),
+        /* LINEAR MEMORY */
+        "load8u" => Some(TokenType::LOAD8U),
+        "load8i" => Some(TokenType::LOAD8I),
+        "load16u" => Some(TokenType::LOAD16U),
+        "load16i" => Some(TokenType::LOAD16I),
+        "load32u" => Some(TokenType::LOAD32U),
+        "load32i" => Some(TokenType::LOAD32I),
+        "load64u" => Some(TokenType::LOAD64U),
+        "load64i" => Some(TokenType::LOAD64I),
+        "store8" => Some(TokenType::STORE8),
+        "store16" => Some(TokenType::STORE16),
+        "store32" => Some(TokenType::STORE32),
+        "store64" => Some(TokenType::STORE64),
+        "memsize" => Some(TokenType::MEMSIZE),
+        "memgrow" => Some(TokenType::MEMGROW),
+        "endian" => Some(TokenType::ENDIAN),
+        "little" => Some(TokenType::LITTLE),
+        "big" => Some(TokenType::BIG),
+        /* OBJECTS */
+        "vnew" => Some(TokenType::VNEW),
+        "vpush" => Some(TokenType::VPUSH),
+        "vpop" => Some(TokenType::VPOP),
+        "vget" => Some(TokenType::VGET),
+        "vset" => Some(TokenType::VSET),
+        "vlen" => Some(TokenType::VLEN),
+        "mnew" => Some(TokenType::MNEW),
+        "mset" => Some(TokenType::MSET),
+        "mget" => Some(TokenType::MGET),
+        "mhas" => Some(TokenType::MHAS),
+        "mdel" => Some(TokenType::MDEL),
+        "mlen" => Some(TokenType::MLEN),
+        "ofree" => Some(TokenType::OFREE),
         /* OPERATOR */
         "add" => Some(TokenType::ADD),
         "radd" => Some(TokenType::RADD),
@@ -299,6 +515,22 @@ pub fn match_token_type(src : &str) -> Option<TokenType> {
         "rrsh" => Some(TokenType::RRSHIFT),
         "lsh" => Some(TokenType::LSHIFT),
         "rlsh" => Some(TokenType::RLSHIFT),
+        "ashr" => Some(TokenType::ASHR),
+        "rashr" => Some(TokenType::RASHR),
+        "rotl" => Some(TokenType::ROTL),
+        "rrotl" => Some(TokenType::RROTL),
+        "rotr" => Some(TokenType::ROTR),
+        "rrotr" => Some(TokenType::RROTR),
+        "bnot" => Some(TokenType::BNOT),
+        "rbnot" => Some(TokenType::RBNOT),
+        "popcnt" => Some(TokenType::POPCNT),
+        "rpopcnt" => Some(TokenType::RPOPCNT),
+        "clz" => Some(TokenType::CLZ),
+        "rclz" => Some(TokenType::RCLZ),
+        "ctz" => Some(TokenType::CTZ),
+        "rctz" => Some(TokenType::RCTZ),
+        "neg" => Some(TokenType::NEG),
+        "rneg" => Some(TokenType::RNEG),
         "eq" => Some(TokenType::EQUAL),
         "req" => Some(TokenType::REQUAL),
         "neq" => Some(TokenType::DIFF),
@@ -317,6 +549,38 @@ pub fn match_token_type(src : &str) -> Option<TokenType> {
         "rgt" => Some(TokenType::RGREAT),
         "gte" => Some(TokenType::EGREAT),
         "rgte" => Some(TokenType::REGREAT),
+        "sqrt" => Some(TokenType::SQRT),
+        "rsqrt" => Some(TokenType::RSQRT),
+        "pow" => Some(TokenType::POW),
+        "rpow" => Some(TokenType::RPOW),
+        "exp" => Some(TokenType::EXP),
+        "rexp" => Some(TokenType::REXP),
+        "ln" => Some(TokenType::LN),
+        "rln" => Some(TokenType::RLN),
+        "sin" => Some(TokenType::SIN),
+        "rsin" => Some(TokenType::RSIN),
+        "cos" => Some(TokenType::COS),
+        "rcos" => Some(TokenType::RCOS),
+        "tan" => Some(TokenType::TAN),
+        "rtan" => Some(TokenType::RTAN),
+        "atan2" => Some(TokenType::ATAN2),
+        "ratan2" => Some(TokenType::RATAN2),
+        "floor" => Some(TokenType::FLOOR),
+        "rfloor" => Some(TokenType::RFLOOR),
+        "ceil" => Some(TokenType::CEIL),
+        "rceil" => Some(TokenType::RCEIL),
+        "round" => Some(TokenType::ROUND),
+        "rround" => Some(TokenType::RROUND),
+        "abs" => Some(TokenType::ABS),
+        "rabs" => Some(TokenType::RABS),
+        "min" => Some(TokenType::MIN),
+        "rmin" => Some(TokenType::RMIN),
+        "max" => Some(TokenType::MAX),
+        "rmax" => Some(TokenType::RMAX),
+        "isnan" => Some(TokenType::ISNAN),
+        "risnan" => Some(TokenType::RISNAN),
+        "isinf" => Some(TokenType::ISINF),
+        "risinf" => Some(TokenType::RISINF),
         "f2i" => Some(TokenType::F2I),
         "f2u" => Some(TokenType::F2U),
         "f2b" => Some(TokenType::F2B),
@@ -363,14 +627,77 @@ pub fn match_token_type(src : &str) -> Option<TokenType> {
         "exit" => Some(TokenType::EXIT),
         "nop" => Some(TokenType::NOP),
         "go" => Some(TokenType::GO),
+        "rgo" => Some(TokenType::RGO),
+        "sgo" => Some(TokenType::SGO),
+        ".table" => Some(TokenType::TABLE),
+        "switch" => Some(TokenType::SWITCH),
         "goif" => Some(TokenType::GOIF),
         "rgoif" => Some(TokenType::RGOIF),
+        "goeq" => Some(TokenType::GOEQ),
+        "gone" => Some(TokenType::GONE),
+        "golt" => Some(TokenType::GOLT),
+        "gole" => Some(TokenType::GOLE),
+        "gogt" => Some(TokenType::GOGT),
+        "goge" => Some(TokenType::GOGE),
+        "goz" => Some(TokenType::GOZ),
+        "gonz" => Some(TokenType::GONZ),
+        "loop" => Some(TokenType::LOOP),
+        "gofz" => Some(TokenType::GOFZ),
+        "gofnz" => Some(TokenType::GOFNZ),
+        "gofn" => Some(TokenType::GOFN),
+        "gofnn" => Some(TokenType::GOFNN),
+        "gofc" => Some(TokenType::GOFC),
+        "gofnc" => Some(TokenType::GOFNC),
+        "gofv" => Some(TokenType::GOFV),
+        "gofnv" => Some(TokenType::GOFNV),
         "call" => Some(TokenType::CALL),
         "callp" => Some(TokenType::CALLP),
         "scall" => Some(TokenType::SCALL),
         "scallp" => Some(TokenType::SCALLP),
         "rcall" => Some(TokenType::RCALL),
         "rcallp" => Some(TokenType::RCALLP),
+        "tailcall" => Some(TokenType::TAILCALL),
+        "pushr" => Some(TokenType::PUSHR),
+        "popr" => Some(TokenType::POPR),
+        "spawn" => Some(TokenType::SPAWN),
+        "yield" => Some(TokenType::YIELD),
+        "resume" => Some(TokenType::RESUME),
+        "status" => Some(TokenType::STATUS),
+        "chan_new" => Some(TokenType::CHANNEW),
+        "send" => Some(TokenType::SEND),
+        "try_send" => Some(TokenType::TRYSEND),
+        "recv" => Some(TokenType::RECV),
+        "try_recv" => Some(TokenType::TRYRECV),
+        "spawn_vm" => Some(TokenType::SPAWNVM),
+        "vector" => Some(TokenType::VECTOR),
+        "raise" => Some(TokenType::RAISE),
+        "iret" => Some(TokenType::IRET),
+        "timer" => Some(TokenType::TIMER),
+        "imask" => Some(TokenType::IMASK),
+        "iunmask" => Some(TokenType::IUNMASK),
+        "pixel" => Some(TokenType::PIXEL),
+        "clear" => Some(TokenType::CLEAR),
+        "present" => Some(TokenType::PRESENT),
+        "fopen" => Some(TokenType::FOPEN),
+        "fread" => Some(TokenType::FREAD),
+        "fwrite" => Some(TokenType::FWRITE),
+        "fseek" => Some(TokenType::FSEEK),
+        "fclose" => Some(TokenType::FCLOSE),
+        "listen" => Some(TokenType::LISTEN),
+        "connect" => Some(TokenType::CONNECT),
+        "accept" => Some(TokenType::ACCEPT),
+        "sock_send" => Some(TokenType::SOCKSEND),
+        "sock_recv" => Some(TokenType::SOCKRECV),
+        "sock_port" => Some(TokenType::SOCKPORT),
+        "sock_close" => Some(TokenType::SOCKCLOSE),
+        "argc" => Some(TokenType::ARGC),
+        "argv" => Some(TokenType::ARGV),
+        "getenv" => Some(TokenType::GETENV),
+        "random" => Some(TokenType::RANDOM),
+        "randf" => Some(TokenType::RANDF),
+        "seed" => Some(TokenType::SEED),
+        "clock" => Some(TokenType::CLOCK),
+        "sleep" => Some(TokenType::SLEEP),
         "ret" => Some(TokenType::RET),
         _ => None
     }
@@ -1,19 +1,27 @@
 use std::path::PathBuf;
+use std::net::Ipv4Addr;
 
 use kwargs::utils::arg_parser::{Parser, Value};
 use kwargs::utils::arg_parser::Value::Flag;
 use vulkyn::asm::asm::Vasm;
 use vulkyn::vm::vm::Vulkyn;
+use vulkyn::vm::device::{Console, Clock, CONSOLE_BASE, CLOCK_BASE};
 
 #[derive(Debug)]
 enum Arg{
    VasmFile(String),
-   VkFile(String)
+   VkFile(String),
+   Root(String),
+   Hosts(Vec<Ipv4Addr>),
+   Words(Vec<String>),
+   Seed(u64)
 }
 #[derive(Debug)]
 enum ErrorArg{
     NotValidVasmFile,
-    NotValidVkFile
+    NotValidVkFile,
+    NotValidHost,
+    NotValidSeed
 }
 
 fn main() {
@@ -39,7 +47,31 @@ fn main() {
         }
         Err(ErrorArg::NotValidVkFile)
     });
+    args.arg("root", "root", "directory the file instructions are confined to",
+    |s| {
+        return Ok(Arg::Root(s))
+    });
+    args.arg("net", "net", "comma separated ipv4 hosts the socket instructions may listen on and connect to",
+    |s| {
+        let hosts : Result<Vec<Ipv4Addr>,_> = s.split(',').map(|host| host.trim().parse()).collect();
+        hosts.map(Arg::Hosts).map_err(|_| ErrorArg::NotValidHost)
+    });
+    args.arg("args", "a", "space separated arguments given to the program",
+    |s| {
+        return Ok(Arg::Words(s.split_whitespace().map(str::to_owned).collect()))
+    });
+    args.arg("env", "e", "comma separated environment variables visible to the program",
+    |s| {
+        return Ok(Arg::Words(s.split(',').map(|name| name.trim().to_owned()).collect()))
+    });
+    args.arg("seed", "s", "seed of the random instructions",
+    |s| {
+        s.parse().map(Arg::Seed).map_err(|_| ErrorArg::NotValidSeed)
+    });
+    args.flag("wallclock", "wc", "clock and sleep use the real time instead of the virtual clock",false);
+    args.flag("writable", "w", "allow the file instructions to write within the root",false);
     args.flag("debug", "d", "debug mode",false);
+    args.flag("checked", "c", "heap accesses require a pointer within its allocation",false);
     
     let parsed_args = args.parse();
     if parsed_args.is_err() {
@@ -72,6 +104,40 @@ fn main() {
         let Ok(mut vulkyn) = Vulkyn::build(&path) else {
             panic!("Something went wrong when opening file {:?}",copy);
         };
+        if let Some(Flag(true)) = parsed_args.get("checked") {
+            vulkyn.set_checked(true);
+        }
+        if let Some(Value::Value(Arg::Root(root))) = parsed_args.get("root") {
+            let writable = matches!(parsed_args.get("writable"), Some(Flag(true)));
+            if vulkyn.grant_files(&PathBuf::from(root), writable).is_err() {
+                panic!("Something went wrong when opening the root {:?}",root);
+            }
+        }
+        if let Some(Value::Value(Arg::Seed(seed))) = parsed_args.get("seed") {
+            vulkyn.set_seed(*seed);
+        }
+        if let Some(Flag(true)) = parsed_args.get("wallclock") {
+            vulkyn.set_wall_clock(true);
+        }
+        // the program sees its own file as first argument
+        let mut program_args = vec![file.clone()];
+        if let Some(Value::Value(Arg::Words(words))) = parsed_args.get("args") {
+            program_args.extend(words.iter().cloned());
+        }
+        vulkyn.set_args(program_args);
+        if let Some(Value::Value(Arg::Words(names))) = parsed_args.get("env") {
+            for name in names {
+                if let Ok(value) = std::env::var(name) {
+                    vulkyn.set_env(name, &value);
+                }
+            }
+        }
+        if let Some(Value::Value(Arg::Hosts(hosts))) = parsed_args.get("net") {
+            vulkyn.grant_net(hosts.clone());
+        }
+        let memory = vulkyn.memory_mut();
+        let _ = memory.map_device(CONSOLE_BASE, Box::new(Console::build()));
+        let _ = memory.map_device(CLOCK_BASE, Box::new(Clock::build()));
         vulkyn.exec();
     }
 }
\ No newline at end of file
@@ -0,0 +1,125 @@
+use std::{fmt::Debug, io::{self, Read, Write}, time::{Instant, SystemTime, UNIX_EPOCH}};
+
+use super::{word::Word, memory::MemoryError};
+
+// Default base addresses used by the command line runner, far above the heap
+pub const CONSOLE_BASE: usize = 0xFFFF_0000;
+pub const CLOCK_BASE: usize = 0xFFFF_1000;
+
+// Memory mapped device : heap accesses within its range are routed to it.
+// Offsets are in words from the base address of the device.
+pub trait Device : Debug + Send {
+    // Number of words mapped
+    fn size(&self) -> usize;
+    fn read(&mut self,offset : usize) -> Result<Word,MemoryError>;
+    fn write(&mut self,offset : usize,word : Word) -> Result<(),MemoryError>;
+}
+
+// Character console
+//  0 : reading takes the next input character, I64(-1) at the end of the input,
+//      writing prints the word as a character
+//  1 : writing prints the word as a number, reads as 0
+pub struct Console {
+    input : Box<dyn Read + Send>,
+    output : Box<dyn Write + Send>,
+}
+
+impl Console {
+    pub fn build() -> Self {
+        Self::with(Box::new(io::stdin()), Box::new(io::stdout()))
+    }
+
+    pub fn with(input : Box<dyn Read + Send>,output : Box<dyn Write + Send>) -> Self {
+        Self { input, output }
+    }
+
+    // Decode the next utf-8 character of the input
+    fn next_char(&mut self) -> Option<char> {
+        let mut bytes = Vec::with_capacity(4);
+        let mut byte = [0u8];
+        while bytes.len() < 4 {
+            if self.input.read(&mut byte).ok()? == 0 {
+                return None;
+            }
+            bytes.push(byte[0]);
+            if let Ok(text) = std::str::from_utf8(&bytes) {
+                return text.chars().next();
+            }
+        }
+        return Some(char::REPLACEMENT_CHARACTER);
+    }
+}
+
+impl Debug for Console {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Console")
+    }
+}
+
+impl Device for Console {
+    fn size(&self) -> usize {
+        2
+    }
+
+    fn read(&mut self,offset : usize) -> Result<Word,MemoryError> {
+        match offset {
+            0 => match self.next_char() {
+                Some(c) => Ok(Word::CHAR(c)),
+                None => Ok(Word::I64(-1)),
+            },
+            1 => Ok(Word::U64(0)),
+            _ => Err(MemoryError::HeapSegmentationFault),
+        }
+    }
+
+    fn write(&mut self,offset : usize,word : Word) -> Result<(),MemoryError> {
+        let res = match (offset,word) {
+            (0,Word::CHAR(c)) => write!(self.output, "{}", c),
+            (0,word) => {
+                let c = char::from_u32(word.as_usize() as u32).unwrap_or(char::REPLACEMENT_CHARACTER);
+                write!(self.output, "{}", c)
+            }
+            (1,word) => write!(self.output, "{}", word),
+            _ => return Err(MemoryError::HeapSegmentationFault),
+        };
+        if res.and_then(|_| self.output.flush()).is_err() {
+            return Err(MemoryError::HeapSegmentationFault);
+        }
+        return Ok(());
+    }
+}
+
+// Read only real time clock
+//  0 : seconds since the unix epoch
+//  1 : milliseconds since the unix epoch
+//  2 : nanoseconds since the clock was built, never goes back
+#[derive(Debug)]
+pub struct Clock {
+    start : Instant,
+}
+
+impl Clock {
+    pub fn build() -> Self {
+        Self { start : Instant::now() }
+    }
+}
+
+impl Device for Clock {
+    fn size(&self) -> usize {
+        3
+    }
+
+    fn read(&mut self,offset : usize) -> Result<Word,MemoryError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        match offset {
+            0 => Ok(Word::U64(now.as_secs() as usize)),
+            1 => Ok(Word::U64(now.as_millis() as usize)),
+            2 => Ok(Word::U64(self.start.elapsed().as_nanos() as usize)),
+            _ => Err(MemoryError::HeapSegmentationFault),
+        }
+    }
+
+    fn write(&mut self,_offset : usize,_word : Word) -> Result<(),MemoryError> {
+        return Err(MemoryError::HeapSegmentationFault);
+    }
+}
@@ -0,0 +1,135 @@
+use std::{collections::HashMap, fs::{self, File, OpenOptions}, io::{self, Read, Seek, SeekFrom, Write}, path::{Component, Path, PathBuf}};
+
+use serde::{Serialize, Deserialize};
+
+// Error codes pushed by the file instructions, results are never negative
+pub const FILE_DENIED: isize = -1;
+pub const FILE_NOT_FOUND: isize = -2;
+pub const FILE_INVALID: isize = -3;
+pub const FILE_ERROR: isize = -4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FileMode {
+    Read,
+    // create or truncate
+    Write,
+    // create, writes go to the end
+    Append,
+    // the file must exist
+    ReadWrite,
+}
+
+impl FileMode {
+    pub fn from_code(code : usize) -> Option<Self> {
+        match code {
+            0 => Some(FileMode::Read),
+            1 => Some(FileMode::Write),
+            2 => Some(FileMode::Append),
+            3 => Some(FileMode::ReadWrite),
+            _ => None,
+        }
+    }
+
+    fn writes(&self) -> bool {
+        *self != FileMode::Read
+    }
+}
+
+// Files opened by a program, all of them within the root granted by the host.
+// Without root every open is denied.
+#[derive(Debug, Default)]
+pub struct Files {
+    root : Option<PathBuf>,
+    writable : bool,
+    files : HashMap<usize, File>,
+    next_handle : usize,
+}
+
+impl Files {
+    pub fn build() -> Self {
+        Self::default()
+    }
+
+    pub fn grant(&mut self,root : &Path,writable : bool) -> io::Result<()> {
+        self.root = Some(fs::canonicalize(root)?);
+        self.writable = writable;
+        return Ok(());
+    }
+
+    // Relative path without parent components, whose existing part resolves,
+    // symbolic links included, within the root
+    fn resolve(&self,path : &str) -> Result<PathBuf,isize> {
+        let Some(root) = &self.root else {
+            return Err(FILE_DENIED);
+        };
+        let relative = Path::new(path);
+        if relative.components().any(|component| !matches!(component, Component::Normal(_) | Component::CurDir)) {
+            return Err(FILE_DENIED);
+        }
+        let full = root.join(relative);
+        // a dangling link is refused, opening it could create a file anywhere
+        let resolved = if fs::symlink_metadata(&full).is_ok() {
+            fs::canonicalize(&full).map_err(|_| FILE_DENIED)?
+        }else {
+            let parent = full.parent().ok_or(FILE_DENIED)?;
+            fs::canonicalize(parent).map_err(|_| FILE_NOT_FOUND)?
+        };
+        if !resolved.starts_with(root) {
+            return Err(FILE_DENIED);
+        }
+        return Ok(full);
+    }
+
+    // Handles start at 1
+    pub fn open(&mut self,path : &str,mode : FileMode) -> Result<usize,isize> {
+        if mode.writes() && !self.writable {
+            return Err(FILE_DENIED);
+        }
+        let path = self.resolve(path)?;
+        let mut options = OpenOptions::new();
+        match mode {
+            FileMode::Read => options.read(true),
+            FileMode::Write => options.write(true).create(true).truncate(true),
+            FileMode::Append => options.append(true).create(true),
+            FileMode::ReadWrite => options.read(true).write(true),
+        };
+        let file = options.open(path).map_err(io_code)?;
+        self.next_handle += 1;
+        self.files.insert(self.next_handle, file);
+        return Ok(self.next_handle);
+    }
+
+    fn file(&mut self,handle : usize) -> Result<&mut File,isize> {
+        self.files.get_mut(&handle).ok_or(FILE_INVALID)
+    }
+
+    pub fn read(&mut self,handle : usize,count : usize) -> Result<Vec<u8>,isize> {
+        let mut bytes = Vec::with_capacity(count);
+        self.file(handle)?.take(count as u64).read_to_end(&mut bytes).map_err(io_code)?;
+        return Ok(bytes);
+    }
+
+    pub fn write(&mut self,handle : usize,bytes : &[u8]) -> Result<usize,isize> {
+        self.file(handle)?.write_all(bytes).map_err(io_code)?;
+        return Ok(bytes.len());
+    }
+
+    // A negative offset is taken from the end of the file
+    pub fn seek(&mut self,handle : usize,offset : isize) -> Result<usize,isize> {
+        let from = if offset < 0 { SeekFrom::End(offset as i64) } else { SeekFrom::Start(offset as u64) };
+        let position = self.file(handle)?.seek(from).map_err(io_code)?;
+        return Ok(position as usize);
+    }
+
+    pub fn close(&mut self,handle : usize) -> Result<(),isize> {
+        self.files.remove(&handle).map(|_| ()).ok_or(FILE_INVALID)
+    }
+}
+
+fn io_code(error : io::Error) -> isize {
+    match error.kind() {
+        io::ErrorKind::NotFound => FILE_NOT_FOUND,
+        io::ErrorKind::PermissionDenied => FILE_DENIED,
+        _ => FILE_ERROR,
+    }
+}
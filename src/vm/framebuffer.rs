@@ -0,0 +1,162 @@
+use std::{fs, io, path::PathBuf};
+
+// File format of the presented frames
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FrameFormat {
+    Ppm,
+    Png,
+}
+
+// Pixels are 0xRRGGBB colors, row by row from the top left corner.
+// Every PRESENT writes the frame to <prefix>NNNN.ppm (or .png), NNNN being the frame number.
+#[derive(Debug)]
+pub struct Framebuffer {
+    width : usize,
+    height : usize,
+    pixels : Vec<u32>,
+    prefix : Option<PathBuf>,
+    format : FrameFormat,
+    frames : usize,
+}
+
+impl Framebuffer {
+    // A framebuffer without prefix only counts the presented frames
+    pub fn build(width : usize,height : usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels : vec![0;width*height],
+            prefix : None,
+            format : FrameFormat::Ppm,
+            frames : 0,
+        }
+    }
+
+    pub fn with_output(mut self,prefix : PathBuf,format : FrameFormat) -> Self {
+        self.prefix = Some(prefix);
+        self.format = format;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn pixel(&self,x : usize,y : usize) -> Option<u32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        return Some(self.pixels[y*self.width + x]);
+    }
+
+    // False when the pixel is out of the frame
+    pub fn set_pixel(&mut self,x : usize,y : usize,color : u32) -> bool {
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+        self.pixels[y*self.width + x] = color & 0xFF_FF_FF;
+        return true;
+    }
+
+    pub fn clear(&mut self,color : u32) {
+        self.pixels.fill(color & 0xFF_FF_FF);
+    }
+
+    pub fn present(&mut self) -> io::Result<()> {
+        if let Some(prefix) = &self.prefix {
+            let extension = match self.format {
+                FrameFormat::Ppm => "ppm",
+                FrameFormat::Png => "png",
+            };
+            let path = PathBuf::from(format!("{}{:04}.{}", prefix.display(), self.frames, extension));
+            let bytes = match self.format {
+                FrameFormat::Ppm => self.ppm(),
+                FrameFormat::Png => self.png(),
+            };
+            fs::write(path, bytes)?;
+        }
+        self.frames += 1;
+        return Ok(());
+    }
+
+    fn rgb(&self) -> impl Iterator<Item = u8> + '_ {
+        self.pixels.iter().flat_map(|color| [(color >> 16) as u8, (color >> 8) as u8, *color as u8])
+    }
+
+    pub fn ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.rgb());
+        return bytes;
+    }
+
+    // 8 bit RGB png with stored, uncompressed, deflate blocks
+    pub fn png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width*3 + 1)*self.height);
+        let rgb : Vec<u8> = self.rgb().collect();
+        for row in rgb.chunks(self.width.max(1)*3).take(self.height) {
+            // filter type none
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+        let mut zlib = vec![0x78, 0x01];
+        let blocks : Vec<&[u8]> = if raw.is_empty() { vec![&[]] } else { raw.chunks(0xFFFF).collect() };
+        for (i,block) in blocks.iter().enumerate() {
+            zlib.push((i + 1 == blocks.len()) as u8);
+            let len = block.len() as u16;
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // bit depth 8, color type rgb, default compression, filter and interlace
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut bytes = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        png_chunk(&mut bytes, b"IHDR", &header);
+        png_chunk(&mut bytes, b"IDAT", &zlib);
+        png_chunk(&mut bytes, b"IEND", &[]);
+        return bytes;
+    }
+}
+
+fn png_chunk(bytes : &mut Vec<u8>,kind : &[u8;4],data : &[u8]) {
+    bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend_from_slice(kind);
+    bytes.extend_from_slice(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data : &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    return !crc;
+}
+
+fn adler32(data : &[u8]) -> u32 {
+    let (mut a,mut b) = (1u32,0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    return (b << 16) | a;
+}
@@ -0,0 +1,39 @@
+use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
+
+// Interrupt numbers go from 0 to INTERRUPTS - 1, the lowest pending one is served first
+pub const INTERRUPTS: usize = 64;
+
+// Pending interrupts of a vm, the host keeps a clone to raise them
+// from another thread while the vm runs
+#[derive(Debug, Clone, Default)]
+pub struct InterruptLine {
+    pending : Arc<AtomicU64>,
+}
+
+impl InterruptLine {
+    pub fn build() -> Self {
+        Self::default()
+    }
+
+    // Numbers outside of the vector table are ignored
+    pub fn raise(&self,number : usize) {
+        if number < INTERRUPTS {
+            self.pending.fetch_or(1 << number, Ordering::SeqCst);
+        }
+    }
+
+    pub fn pending(&self) -> u64 {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    // Take the lowest pending interrupt not masked
+    pub(crate) fn take(&self,mask : u64) -> Option<usize> {
+        let ready = self.pending() & !mask;
+        if ready == 0 {
+            return None;
+        }
+        let number = ready.trailing_zeros() as usize;
+        self.pending.fetch_and(!(1 << number), Ordering::SeqCst);
+        return Some(number);
+    }
+}
@@ -1,8 +1,9 @@
 
-use std::{collections::{HashMap}};
+use std::{collections::{HashMap, BTreeMap}};
 
+use serde::{Serialize, Deserialize};
 
-use super::{word::Word, register::{Registers, Register}};
+use super::{word::Word, register::{Registers, Register}, device::Device};
 
 #[derive(Debug)]
 pub enum MemoryError {
@@ -10,12 +11,64 @@ pub enum MemoryError {
     StackOverflow,
     StackSegmentationFault,
     HeapSegmentationFault,
+    LinearSegmentationFault,
+    InvalidObject,
+    ObjectOutOfRange,
+    DeviceOverlap,
+}
+
+// VM managed collection, addressed by a handle
+#[derive(Debug)]
+pub enum Object {
+    Vector(Vec<Word>),
+    Map(HashMap<Word,Word>),
+}
+
+// Byte order of the linear memory
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+// Stack and registers of a coroutine while it is swapped out of the memory
+#[derive(Debug)]
+pub struct Context {
+    stack : Vec<Word>,
+    saves : Vec<Word>,
+    stack_size : usize,
+    registers : Registers,
+}
+
+impl Context {
+    // Start at entry with the given words on the stack
+    pub fn build(entry : usize,stack : Vec<Word>) -> Self {
+        let mut registers = Registers::init();
+        registers.set(Register::Ni, Word::U64(entry));
+        registers.set(Register::Ts, Word::U64(stack.len().saturating_sub(1)));
+        Self {
+            stack_size : stack.len(),
+            stack,
+            saves : Vec::new(),
+            registers,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Memory{
     stack : Vec<Word>,
+    saves : Vec<Word>,
     heap : HashMap<usize, Word>,
+    // base address -> size in words of every live allocation
+    allocations : BTreeMap<usize, usize>,
+    next_address : usize,
+    // base address -> device mapped from it
+    devices : BTreeMap<usize, Box<dyn Device>>,
+    linear : Vec<u8>,
+    objects : HashMap<usize, Object>,
+    next_handle : usize,
+    pub endian : Endian,
     pub stack_size : usize,
     pub registers : Registers,
 }
@@ -24,7 +77,15 @@ impl Memory{
     pub fn build() -> Self {
         Self {
             stack : Vec::new(),
+            saves : Vec::new(),
             heap : HashMap::new(),
+            allocations : BTreeMap::new(),
+            next_address : 16,
+            devices : BTreeMap::new(),
+            linear : Vec::new(),
+            objects : HashMap::new(),
+            next_handle : 0,
+            endian : Endian::Little,
             stack_size : 0,
             registers : Registers::init(),
         }
@@ -42,12 +103,15 @@ impl Memory{
         self.registers.set(Register::Ts, Word::U64(self.stack_size-1));
     }
 
+    // Insert the word below the idx words on top of the stack
     pub fn insert(&mut self, word : Word,idx : usize) -> Result<Word,MemoryError> {
-        if self.stack_size < idx {
+        let size = self.stack.len();
+        if size < idx {
             return Err(MemoryError::StackUnderflow);
         }
-        self.stack.insert(self.stack_size - idx, word);
-        return Ok(Word::U64(self.stack_size - idx))
+        self.stack.insert(size - idx, word);
+        self.update_top();
+        return Ok(Word::U64(size - idx))
     }
 
     pub fn pop(&mut self) -> Result<Word,MemoryError> {
@@ -80,6 +144,44 @@ impl Memory{
         }
     }
 
+    fn update_top(&mut self) {
+        self.stack_size = self.stack.len();
+        self.registers.set(Register::Ts, Word::U64(self.stack_size.saturating_sub(1)));
+    }
+
+    // Push a copy of the n-th word below the top, 0 being the top
+    pub fn pick(&mut self, n : usize) -> Result<Word,MemoryError> {
+        let size = self.stack.len();
+        if n >= size {
+            return Err(MemoryError::StackUnderflow);
+        }
+        let word = self.stack[size - 1 - n];
+        self.stack.push(word);
+        self.update_top();
+        return Ok(word);
+    }
+
+    // Move the n-th word below the top to the top
+    pub fn roll(&mut self, n : usize) -> Result<Word,MemoryError> {
+        let size = self.stack.len();
+        if n >= size {
+            return Err(MemoryError::StackUnderflow);
+        }
+        let word = self.stack.remove(size - 1 - n);
+        self.stack.push(word);
+        return Ok(word);
+    }
+
+    pub fn drop(&mut self, n : usize) -> Result<(),MemoryError> {
+        let size = self.stack.len();
+        if n > size {
+            return Err(MemoryError::StackUnderflow);
+        }
+        self.stack.truncate(size - n);
+        self.update_top();
+        return Ok(());
+    }
+
     pub fn stack_read(&self, addr : Word) -> Result<Word,MemoryError>{
         let idx = addr.as_usize();
         let Some(word) = self.stack.get(idx) else {
@@ -96,19 +198,47 @@ impl Memory{
         return Ok(res);
     }
     pub fn stack_clean(&mut self,start : usize,end : usize) -> Result<(),MemoryError> {
-        if end > self.stack_size {
+        if end > self.stack.len() || start > end {
             return Err(MemoryError::StackOverflow);
         }
         self.stack.drain(start..end);
-        self.stack_size = self.stack.len();
+        self.update_top();
         return Ok(())
     }
 
+    // Exchange the stack and registers with the ones of the context,
+    // the heap, linear memory and objects stay shared
+    pub fn swap_context(&mut self,context : &mut Context) {
+        std::mem::swap(&mut self.stack, &mut context.stack);
+        std::mem::swap(&mut self.saves, &mut context.saves);
+        std::mem::swap(&mut self.stack_size, &mut context.stack_size);
+        std::mem::swap(&mut self.registers, &mut context.registers);
+    }
+
+    /* REGISTER SAVES */
+    // Saved registers live apart from the stack, so values pushed
+    // after a save stay on top
+    pub fn save(&mut self,word : Word) {
+        self.saves.push(word);
+    }
+
+    pub fn restore(&mut self) -> Result<Word,MemoryError> {
+        let Some(word) = self.saves.pop() else {
+            return Err(MemoryError::StackUnderflow);
+        };
+        return Ok(word);
+    }
+
     /* HEAP ACCESS */
     pub fn read(&mut self,idx:Word,size:usize,offset:usize) -> Result<Vec<Word>,MemoryError> {
         let mut res : Vec<Word> = Vec::with_capacity(size);
         for i in (0..size*16).step_by(16) {        
-            let Some(word) = self.heap.get(&(idx.as_usize() + offset*16 + i)) else {
+            let addr = idx.as_usize() + offset*16 + i;
+            if let Some((device,offset)) = self.device_at(addr) {
+                res.push(device.read(offset)?);
+                continue;
+            }
+            let Some(word) = self.heap.get(&addr) else {
                 return Err(MemoryError::HeapSegmentationFault);
             };
             res.push(*word);
@@ -118,6 +248,10 @@ impl Memory{
 
     pub fn write(&mut self,word : Word,idx : Word,offset:isize) -> Result<Word,MemoryError> {
         let addr = &((idx.as_usize() as isize + offset*16) as usize);
+        if let Some((device,offset)) = self.device_at(*addr) {
+            device.write(offset, word)?;
+            return Ok(word);
+        }
         if !self.heap.contains_key(addr) {
             return Err(MemoryError::HeapSegmentationFault);
         }
@@ -127,19 +261,33 @@ impl Memory{
         return Ok(word);
     }
 
+    // Allocations are 16 bytes per word and never reuse an address,
+    // 0 stays the null pointer
     pub fn alloc(&mut self,size:usize)  -> Result<Word,MemoryError>{
-        let mut words: Vec<Word> = vec![Word::init();size];
+        let mut base = self.next_address;
+        // allocations never overlap a mapped device
+        while let Some(end) = self.devices.iter()
+            .map(|(start,device)| (*start,start + device.size()*16))
+            .find(|(start,end)| *start < base + size.max(1)*16 && base < *end)
+            .map(|(_,end)| end) {
+            base = end;
+        }
         for i in 0..size {
-            let addr = {
-                let a = (&words[i] as *const Word);
-                a.clone() as usize
-            };
-            self.heap.insert( addr as usize, words[i]);
+            self.heap.insert(base + i*16, Word::init());
         }
-        return Ok(Word::U64((&words[0] as *const Word) as usize));
+        self.allocations.insert(base, size);
+        self.next_address = base + size.max(1)*16;
+        return Ok(Word::PTR(base));
     }
 
+    // Freeing the base of an allocation releases all of its words
     pub fn free(&mut self,idx : Word)  -> Result<(),MemoryError> {
+        if let Some(size) = self.allocations.remove(&idx.as_usize()) {
+            for i in 0..size {
+                self.heap.remove(&(idx.as_usize() + i*16));
+            }
+            return Ok(());
+        }
         let Some(word) = self.heap.get(&idx.as_usize()) else {
             return Err(MemoryError::HeapSegmentationFault);
         };
@@ -147,4 +295,250 @@ impl Memory{
         self.heap.remove(&idx.as_usize());
         return Ok(()) 
     }
-}
\ No newline at end of file
+
+    // (base, size in words) of the allocation holding the address,
+    // the address just past its end included
+    pub fn allocation(&self,idx : Word) -> Option<(usize,usize)> {
+        let addr = idx.as_usize();
+        let (base,size) = self.allocations.range(..=addr).next_back()?;
+        if addr > base + size*16 {
+            return None;
+        }
+        return Some((*base,*size));
+    }
+
+    /* DEVICES */
+    // Map the device from base, its range must not overlap another device
+    // or a live heap word
+    pub fn map_device(&mut self,base : usize,device : Box<dyn Device>) -> Result<(),MemoryError> {
+        let end = base + device.size()*16;
+        let overlap = self.devices.iter().any(|(start,other)| *start < end && base < start + other.size()*16)
+            || self.heap.keys().any(|addr| base <= *addr && *addr < end);
+        if overlap {
+            return Err(MemoryError::DeviceOverlap);
+        }
+        self.devices.insert(base, device);
+        return Ok(());
+    }
+
+    pub fn unmap_device(&mut self,base : usize) -> Option<Box<dyn Device>> {
+        self.devices.remove(&base)
+    }
+
+    // Device and word offset of a word address
+    fn device_at(&mut self,addr : usize) -> Option<(&mut Box<dyn Device>,usize)> {
+        let (base,device) = self.devices.range_mut(..=addr).next_back()?;
+        let offset = addr - base;
+        if offset % 16 != 0 || offset / 16 >= device.size() {
+            return None;
+        }
+        return Some((device,offset / 16));
+    }
+
+    // Whether the count words from addr are all mapped to one device
+    pub fn device_range(&self,addr : Word,count : usize) -> bool {
+        let addr = addr.as_usize();
+        let Some((base,device)) = self.devices.range(..=addr).next_back() else {
+            return false;
+        };
+        return (addr - base) % 16 == 0 && addr + count*16 <= base + device.size()*16;
+    }
+
+    /* HEAP BULK ACCESS */
+    fn heap_range(&self,idx : Word,count : usize) -> Result<(),MemoryError> {
+        for i in 0..count {
+            if !self.heap.contains_key(&(idx.as_usize() + i*16)) {
+                return Err(MemoryError::HeapSegmentationFault);
+            }
+        }
+        return Ok(());
+    }
+
+    // Copy count words from src to dst, the two ranges may overlap
+    pub fn copy(&mut self,dst : Word,src : Word,count : usize) -> Result<(),MemoryError> {
+        self.heap_range(dst, count)?;
+        let words = self.read(src, count, 0)?;
+        for (i,word) in words.into_iter().enumerate() {
+            self.heap.insert(dst.as_usize() + i*16, word);
+        }
+        return Ok(());
+    }
+
+    pub fn fill(&mut self,dst : Word,word : Word,count : usize) -> Result<(),MemoryError> {
+        self.heap_range(dst, count)?;
+        for i in 0..count {
+            self.heap.insert(dst.as_usize() + i*16, word);
+        }
+        return Ok(());
+    }
+
+    // -1, 0 or 1 as an I64 from the first pair of words that differ,
+    // words that can not be ordered compare by their raw bits
+    pub fn compare(&self,lhs : Word,rhs : Word,count : usize) -> Result<Word,MemoryError> {
+        self.heap_range(lhs, count)?;
+        self.heap_range(rhs, count)?;
+        for i in 0..count {
+            let x = self.heap[&(lhs.as_usize() + i*16)];
+            let y = self.heap[&(rhs.as_usize() + i*16)];
+            let ordering = match x.partial_cmp(&y) {
+                Some(ordering) => ordering,
+                None => x.to_bits().cmp(&y.to_bits()),
+            };
+            match ordering {
+                std::cmp::Ordering::Less => return Ok(Word::I64(-1)),
+                std::cmp::Ordering::Greater => return Ok(Word::I64(1)),
+                std::cmp::Ordering::Equal => {}
+            }
+        }
+        return Ok(Word::I64(0));
+    }
+
+    /* LINEAR MEMORY ACCESS */
+    pub fn mem_size(&self) -> usize {
+        return self.linear.len();
+    }
+
+    // Grow the linear memory by size zeroed bytes, return the previous size
+    pub fn mem_grow(&mut self,size : usize) -> usize {
+        let previous = self.linear.len();
+        self.linear.resize(previous + size, 0);
+        return previous;
+    }
+
+    pub fn mem_bytes(&self) -> &[u8] {
+        return &self.linear;
+    }
+
+    // Load width bytes (1, 2, 4 or 8), sign extended to an I64 when signed
+    pub fn load_bytes(&self,addr : usize,width : usize,signed : bool) -> Result<Word,MemoryError> {
+        let Some(bytes) = self.linear.get(addr..addr.saturating_add(width)) else {
+            return Err(MemoryError::LinearSegmentationFault);
+        };
+        let mut buffer = [0u8;8];
+        let value = match self.endian {
+            Endian::Little => {
+                buffer[..width].copy_from_slice(bytes);
+                u64::from_le_bytes(buffer)
+            }
+            Endian::Big => {
+                buffer[8-width..].copy_from_slice(bytes);
+                u64::from_be_bytes(buffer)
+            }
+        };
+        if signed {
+            let shift = 64 - 8*width as u32;
+            return Ok(Word::I64(((value << shift) as i64 >> shift) as isize));
+        }
+        return Ok(Word::U64(value as usize));
+    }
+
+    // Store the low width bytes of the word
+    pub fn store_bytes(&mut self,addr : usize,width : usize,word : Word) -> Result<(),MemoryError> {
+        let Some(bytes) = self.linear.get_mut(addr..addr.saturating_add(width)) else {
+            return Err(MemoryError::LinearSegmentationFault);
+        };
+        let value = word.to_bits();
+        match self.endian {
+            Endian::Little => bytes.copy_from_slice(&value.to_le_bytes()[..width]),
+            Endian::Big => bytes.copy_from_slice(&value.to_be_bytes()[8-width..]),
+        }
+        return Ok(());
+    }
+
+    /* OBJECT ACCESS */
+    // Handles start at 1 and are never reused
+    pub fn new_object(&mut self,object : Object) -> Word {
+        self.next_handle += 1;
+        self.objects.insert(self.next_handle, object);
+        return Word::U64(self.next_handle);
+    }
+
+    pub fn object(&self,handle : Word) -> Result<&Object,MemoryError> {
+        let Some(object) = self.objects.get(&handle.as_usize()) else {
+            return Err(MemoryError::InvalidObject);
+        };
+        return Ok(object);
+    }
+
+    pub fn free_object(&mut self,handle : Word) -> Result<(),MemoryError> {
+        let Some(_) = self.objects.remove(&handle.as_usize()) else {
+            return Err(MemoryError::InvalidObject);
+        };
+        return Ok(());
+    }
+
+    // Objects not freed yet, a program that ends with live objects leaks
+    pub fn live_objects(&self) -> usize {
+        return self.objects.len();
+    }
+
+    fn vector(&mut self,handle : Word) -> Result<&mut Vec<Word>,MemoryError> {
+        let Some(Object::Vector(vector)) = self.objects.get_mut(&handle.as_usize()) else {
+            return Err(MemoryError::InvalidObject);
+        };
+        return Ok(vector);
+    }
+
+    fn map(&mut self,handle : Word) -> Result<&mut HashMap<Word,Word>,MemoryError> {
+        let Some(Object::Map(map)) = self.objects.get_mut(&handle.as_usize()) else {
+            return Err(MemoryError::InvalidObject);
+        };
+        return Ok(map);
+    }
+
+    pub fn vector_push(&mut self,handle : Word,word : Word) -> Result<(),MemoryError> {
+        self.vector(handle)?.push(word);
+        return Ok(());
+    }
+
+    pub fn vector_pop(&mut self,handle : Word) -> Result<Word,MemoryError> {
+        let Some(word) = self.vector(handle)?.pop() else {
+            return Err(MemoryError::ObjectOutOfRange);
+        };
+        return Ok(word);
+    }
+
+    pub fn vector_get(&mut self,handle : Word,idx : Word) -> Result<Word,MemoryError> {
+        let Some(word) = self.vector(handle)?.get(idx.as_usize()) else {
+            return Err(MemoryError::ObjectOutOfRange);
+        };
+        return Ok(*word);
+    }
+
+    pub fn vector_set(&mut self,handle : Word,idx : Word,word : Word) -> Result<(),MemoryError> {
+        let Some(slot) = self.vector(handle)?.get_mut(idx.as_usize()) else {
+            return Err(MemoryError::ObjectOutOfRange);
+        };
+        *slot = word;
+        return Ok(());
+    }
+
+    pub fn vector_len(&mut self,handle : Word) -> Result<usize,MemoryError> {
+        return Ok(self.vector(handle)?.len());
+    }
+
+    pub fn map_insert(&mut self,handle : Word,key : Word,word : Word) -> Result<(),MemoryError> {
+        self.map(handle)?.insert(key, word);
+        return Ok(());
+    }
+
+    pub fn map_get(&mut self,handle : Word,key : Word) -> Result<Word,MemoryError> {
+        let Some(word) = self.map(handle)?.get(&key) else {
+            return Err(MemoryError::ObjectOutOfRange);
+        };
+        return Ok(*word);
+    }
+
+    pub fn map_contains(&mut self,handle : Word,key : Word) -> Result<bool,MemoryError> {
+        return Ok(self.map(handle)?.contains_key(&key));
+    }
+
+    pub fn map_remove(&mut self,handle : Word,key : Word) -> Result<(),MemoryError> {
+        self.map(handle)?.remove(&key);
+        return Ok(());
+    }
+
+    pub fn map_len(&mut self,handle : Word) -> Result<usize,MemoryError> {
+        return Ok(self.map(handle)?.len());
+    }
+}
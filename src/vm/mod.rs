@@ -4,4 +4,11 @@ mod test;
 pub mod word;
 pub mod register;
 pub mod vm;
-pub mod memory;
\ No newline at end of file
+pub mod memory;
+pub mod scheduler;
+pub mod interrupt;
+pub mod device;
+pub mod framebuffer;
+pub mod files;
+pub mod net;
+pub mod random;
\ No newline at end of file
@@ -0,0 +1,110 @@
+use std::{collections::HashMap, io::{self, Read, Write}, net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpListener, TcpStream}};
+
+// Error codes pushed by the socket instructions, results are never negative
+pub const NET_DENIED: isize = -1;
+pub const NET_REFUSED: isize = -2;
+pub const NET_INVALID: isize = -3;
+pub const NET_ERROR: isize = -4;
+
+#[derive(Debug)]
+enum Socket {
+    Listener(TcpListener),
+    Stream(TcpStream),
+}
+
+// Tcp sockets of a program, only to or on the hosts granted by the host.
+// Without grant every listen and connect is denied.
+#[derive(Debug, Default)]
+pub struct Net {
+    hosts : Vec<Ipv4Addr>,
+    sockets : HashMap<usize, Socket>,
+    next_handle : usize,
+}
+
+impl Net {
+    pub fn build() -> Self {
+        Self::default()
+    }
+
+    pub fn grant(&mut self,hosts : Vec<Ipv4Addr>) {
+        self.hosts = hosts;
+    }
+
+    fn address(&self,host : u32,port : u16) -> Result<SocketAddr,isize> {
+        let host = Ipv4Addr::from(host);
+        if !self.hosts.contains(&host) {
+            return Err(NET_DENIED);
+        }
+        return Ok(SocketAddr::V4(SocketAddrV4::new(host, port)));
+    }
+
+    // Handles start at 1
+    fn insert(&mut self,socket : Socket) -> usize {
+        self.next_handle += 1;
+        self.sockets.insert(self.next_handle, socket);
+        return self.next_handle;
+    }
+
+    pub fn listen(&mut self,host : u32,port : u16) -> Result<usize,isize> {
+        let address = self.address(host, port)?;
+        let listener = TcpListener::bind(address).map_err(io_code)?;
+        return Ok(self.insert(Socket::Listener(listener)));
+    }
+
+    // Wait for a connection on a listener
+    pub fn accept(&mut self,handle : usize) -> Result<usize,isize> {
+        let Some(Socket::Listener(listener)) = self.sockets.get(&handle) else {
+            return Err(NET_INVALID);
+        };
+        let (stream,_) = listener.accept().map_err(io_code)?;
+        return Ok(self.insert(Socket::Stream(stream)));
+    }
+
+    pub fn connect(&mut self,host : u32,port : u16) -> Result<usize,isize> {
+        let address = self.address(host, port)?;
+        let stream = TcpStream::connect(address).map_err(io_code)?;
+        return Ok(self.insert(Socket::Stream(stream)));
+    }
+
+    fn stream(&mut self,handle : usize) -> Result<&mut TcpStream,isize> {
+        let Some(Socket::Stream(stream)) = self.sockets.get_mut(&handle) else {
+            return Err(NET_INVALID);
+        };
+        return Ok(stream);
+    }
+
+    pub fn send(&mut self,handle : usize,bytes : &[u8]) -> Result<usize,isize> {
+        self.stream(handle)?.write_all(bytes).map_err(io_code)?;
+        return Ok(bytes.len());
+    }
+
+    // Wait for up to count bytes, none once the peer closed the connection
+    pub fn recv(&mut self,handle : usize,count : usize) -> Result<Vec<u8>,isize> {
+        let mut bytes = vec![0;count];
+        let size = self.stream(handle)?.read(&mut bytes).map_err(io_code)?;
+        bytes.truncate(size);
+        return Ok(bytes);
+    }
+
+    // Local port, the one picked by the system for a listener on port 0
+    pub fn port(&self,handle : usize) -> Result<usize,isize> {
+        let address = match self.sockets.get(&handle) {
+            Some(Socket::Listener(listener)) => listener.local_addr(),
+            Some(Socket::Stream(stream)) => stream.local_addr(),
+            None => return Err(NET_INVALID),
+        };
+        return Ok(address.map_err(io_code)?.port() as usize);
+    }
+
+    pub fn close(&mut self,handle : usize) -> Result<(),isize> {
+        self.sockets.remove(&handle).map(|_| ()).ok_or(NET_INVALID)
+    }
+}
+
+fn io_code(error : io::Error) -> isize {
+    match error.kind() {
+        io::ErrorKind::ConnectionRefused => NET_REFUSED,
+        io::ErrorKind::PermissionDenied | io::ErrorKind::AddrNotAvailable => NET_DENIED,
+        _ => NET_ERROR,
+    }
+}
@@ -0,0 +1,34 @@
+// Seedable xorshift64* generator, a given seed always gives the same sequence
+#[derive(Debug, Clone)]
+pub struct Random {
+    state : u64,
+}
+
+impl Random {
+    pub fn build(seed : u64) -> Self {
+        let mut random = Self { state : 0 };
+        random.seed(seed);
+        random
+    }
+
+    // The seed goes through splitmix64 so that 0 and close seeds give good states
+    pub fn seed(&mut self,seed : u64) {
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        self.state = if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z };
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -2,12 +2,12 @@ use serde::{Serialize, Deserialize};
 
 use super::word::Word;
 
+// Size of the general purpose register file, R0 to R31
+pub const GENERAL_REGISTERS: usize = 32;
+
 #[derive(Debug, Copy, Clone,Deserialize,Serialize)]
 pub enum Register{
-    R1,
-    R2,
-    R3,
-    R4,
+    R(u8),// General purpose register, indexed
     Ts,// Top of the stack pointer
     Bs,// Bottom of the stack pointer
     He,// Hermes : runtime flag (ex : negatif ...)
@@ -18,10 +18,7 @@ pub enum Register{
 
 #[derive(Debug, Copy, Clone,Deserialize,Serialize)]
 pub struct Registers {
-    pub R1 : Word,
-    pub R2 : Word,
-    pub R3 : Word,
-    pub R4 : Word,
+    pub general : [Word;GENERAL_REGISTERS],
     pub Ts : Word,// Top of the stack pointer
     pub Bs : Word,// Bottom of the stack pointer
     pub He : Word,// Hermes : runtime flag (ex : negatif ...)
@@ -31,12 +28,15 @@ pub struct Registers {
 }
 
 impl Registers {
+    // A general register outside of the register file reads as zero
+    // and ignores writes
     pub fn set(&mut self,register : Register,word : Word){
         match register {
-            Register::R1 => self.R1 = word,
-            Register::R2 => self.R2 = word,
-            Register::R3 => self.R3 = word,
-            Register::R4 => self.R4 = word,
+            Register::R(idx) => {
+                if let Some(reg) = self.general.get_mut(idx as usize) {
+                    *reg = word;
+                }
+            }
             Register::Ts => self.Ts = word,
             Register::Bs => self.Bs = word,
             Register::He => self.He = word,
@@ -47,10 +47,7 @@ impl Registers {
     }
     pub fn get(&self,register : Register) -> Word{
         match register {
-            Register::R1 => self.R1,
-            Register::R2 => self.R2,
-            Register::R3 => self.R3,
-            Register::R4 => self.R4,
+            Register::R(idx) => self.general.get(idx as usize).copied().unwrap_or(Word::init()),
             Register::Ts => self.Ts,
             Register::Bs => self.Bs,
             Register::He => self.He,
@@ -60,11 +57,8 @@ impl Registers {
         }
     }
     pub fn init() -> Self{
-        Self { 
-            R1: Word::init(),
-            R2: Word::init(),
-            R3: Word::init(),
-            R4: Word::init(),
+        Self {
+            general: [Word::init();GENERAL_REGISTERS],
             Ts: Word::init(),
             Bs: Word::init(),
             He: Word::init(),
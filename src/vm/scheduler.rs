@@ -0,0 +1,170 @@
+use std::{collections::{HashMap, VecDeque}, sync::{Arc, Mutex, Condvar}, thread};
+
+use super::{word::Word, vm::Vulkyn};
+
+#[derive(Debug)]
+pub enum ChannelError {
+    InvalidChannel,
+    // the operation would block, with the progress count seen at that time
+    Blocked(u64),
+}
+
+#[derive(Debug)]
+struct Channel {
+    queue : VecDeque<Word>,
+    // 0 : unbounded
+    capacity : usize,
+}
+
+struct HubState {
+    channels : HashMap<usize, Channel>,
+    next_channel : usize,
+    next_id : usize,
+    // vms waiting for a worker, blocked ones included
+    queue : VecDeque<Vulkyn>,
+    running : usize,
+    results : Vec<Vulkyn>,
+    // bumped by every channel transfer, spawn and exit : a blocked vm
+    // is only retried once the count moved
+    progress : u64,
+}
+
+// Channels and run queue shared by the vms of a scheduler
+pub struct Hub {
+    state : Mutex<HubState>,
+    wake : Condvar,
+}
+
+impl Hub {
+    pub fn build() -> Self {
+        Self {
+            state : Mutex::new(HubState {
+                channels : HashMap::new(),
+                next_channel : 0,
+                next_id : 0,
+                queue : VecDeque::new(),
+                running : 0,
+                results : Vec::new(),
+                progress : 0,
+            }),
+            wake : Condvar::new(),
+        }
+    }
+
+    // Channel handles start at 1
+    pub fn channel(&self,capacity : usize) -> Word {
+        let mut state = self.state.lock().unwrap();
+        state.next_channel += 1;
+        let handle = state.next_channel;
+        state.channels.insert(handle, Channel { queue : VecDeque::new(), capacity });
+        return Word::U64(handle);
+    }
+
+    pub fn send(&self,channel : Word,word : Word) -> Result<(),ChannelError> {
+        let mut state = self.state.lock().unwrap();
+        let progress = state.progress;
+        let Some(channel) = state.channels.get_mut(&channel.as_usize()) else {
+            return Err(ChannelError::InvalidChannel);
+        };
+        if channel.capacity != 0 && channel.queue.len() >= channel.capacity {
+            return Err(ChannelError::Blocked(progress));
+        }
+        channel.queue.push_back(word);
+        state.progress += 1;
+        self.wake.notify_all();
+        return Ok(());
+    }
+
+    pub fn recv(&self,channel : Word) -> Result<Word,ChannelError> {
+        let mut state = self.state.lock().unwrap();
+        let progress = state.progress;
+        let Some(channel) = state.channels.get_mut(&channel.as_usize()) else {
+            return Err(ChannelError::InvalidChannel);
+        };
+        let Some(word) = channel.queue.pop_front() else {
+            return Err(ChannelError::Blocked(progress));
+        };
+        state.progress += 1;
+        self.wake.notify_all();
+        return Ok(word);
+    }
+
+    // Queue the vm and return its id
+    pub fn spawn(&self,mut vulkyn : Vulkyn) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        vulkyn.set_id(id);
+        state.queue.push_back(vulkyn);
+        state.progress += 1;
+        self.wake.notify_all();
+        return id;
+    }
+
+    fn work(&self) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let progress = state.progress;
+            let runnable = state.queue.iter().position(|vulkyn| vulkyn.blocked_at() != Some(progress));
+            let Some(pos) = runnable else {
+                if state.running == 0 {
+                    // nothing runs and every queued vm waits on a channel
+                    while let Some(mut vulkyn) = state.queue.pop_front() {
+                        vulkyn.deadlock();
+                        state.results.push(vulkyn);
+                    }
+                    self.wake.notify_all();
+                    return;
+                }
+                state = self.wake.wait(state).unwrap();
+                continue;
+            };
+            let mut vulkyn = state.queue.remove(pos).unwrap();
+            state.running += 1;
+            drop(state);
+            let ended = vulkyn.slice();
+            state = self.state.lock().unwrap();
+            state.running -= 1;
+            if ended {
+                state.progress += 1;
+                state.results.push(vulkyn);
+            }else {
+                state.queue.push_back(vulkyn);
+            }
+            self.wake.notify_all();
+        }
+    }
+}
+
+// Runs vms on a pool of worker threads until all of them exit.
+// A vm waiting on a channel gives its worker back instead of holding it.
+pub struct Scheduler {
+    hub : Arc<Hub>,
+    workers : usize,
+}
+
+impl Scheduler {
+    pub fn build(workers : usize) -> Self {
+        Self {
+            hub : Arc::new(Hub::build()),
+            workers : workers.max(1),
+        }
+    }
+
+    pub fn spawn(&self,mut vulkyn : Vulkyn) -> usize {
+        vulkyn.attach(self.hub.clone());
+        return self.hub.spawn(vulkyn);
+    }
+
+    // Exited vms sorted by id, their Fl register holds the exit state
+    pub fn run(self) -> Vec<Vulkyn> {
+        thread::scope(|scope| {
+            for _ in 0..self.workers {
+                scope.spawn(|| self.hub.work());
+            }
+        });
+        let mut results = std::mem::take(&mut self.hub.state.lock().unwrap().results);
+        results.sort_by_key(|vulkyn| vulkyn.id());
+        return results;
+    }
+}
@@ -1,8 +1,8 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, io::{Cursor, Read, Write}, sync::{Arc, Mutex}, net::{Ipv4Addr, TcpListener, TcpStream}, thread, time::Duration};
 
-use crate::vm::word::Word;
+use crate::{vm::word::Word, asm::{lexer, parser::Parser}};
 
-use super::{vm::Vulkyn, memory::{self, Memory}};
+use super::{register::Register, vm::{Vulkyn, Program, COND_ZERO, COND_CARRY}, memory::{self, Memory}, scheduler::Scheduler, device::{Console, Clock, CONSOLE_BASE, CLOCK_BASE}, framebuffer::{Framebuffer, FrameFormat}};
 
 
 fn test_file(file : &str) -> PathBuf{
@@ -12,6 +12,23 @@ fn test_file(file : &str) -> PathBuf{
     d.clone()
 }
 
+fn run_src(src : &str) -> Vulkyn {
+    let tokens = lexer::tokenize(src).unwrap();
+    let instructions = Parser::init(tokens).run().unwrap();
+    let mut vulkyn = Vulkyn::load(Program { instructions });
+    vulkyn.exec();
+    vulkyn
+}
+
+fn run_checked(src : &str) -> Vulkyn {
+    let tokens = lexer::tokenize(src).unwrap();
+    let instructions = Parser::init(tokens).run().unwrap();
+    let mut vulkyn = Vulkyn::load(Program { instructions });
+    vulkyn.set_checked(true);
+    vulkyn.exec();
+    vulkyn
+}
+
 
 #[test]
 fn test_addition() {
@@ -36,4 +53,1111 @@ fn test_memory_heap() {
     assert!(read2.is_ok());
     dbg!(&read2);
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_condition_flags() {
+    let vulkyn = run_src("
+        %main
+        push 3
+        push 3
+        sub
+        pop
+        gofz %zero
+        exit
+        %zero
+        rwrite 1 r1
+        push 1
+        push 18446744073709551615
+        add
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(1));
+    assert_eq!(memory.registers.He & COND_CARRY, COND_CARRY);
+    assert_eq!(memory.registers.He & COND_ZERO, COND_ZERO);
+
+    let vulkyn = run_src("
+        rlt -2 -1
+        gofn %less
+        exit
+        %less
+        rwrite true r2
+        exit
+    ");
+    assert_eq!(vulkyn.memory().registers.get(Register::R(2)), Word::BOOL(true));
+}
+
+#[test]
+fn test_float_math() {
+    let vulkyn = run_src("
+        push 2.0
+        rpow 2.0 10.0
+        smove r1
+        sqrt
+        smove r2
+        rmax 1.5 -3.0
+        smove r3
+        risnan nan
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::F64(1024.0));
+    assert_eq!(memory.registers.get(Register::R(2)), Word::F64(2.0_f64.sqrt()));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::F64(1.5));
+    assert_eq!(memory.stack_read(Word::U64(0)).unwrap(), Word::BOOL(true));
+}
+
+#[test]
+fn test_unicode_char() {
+    let vulkyn = run_src("
+        radd 'é' 1
+        smove r1
+        ru2c 300
+        smove r2
+        rc2u '\\u{1F600}'
+        smove r3
+        ru2c 55296
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::CHAR('ê'));
+    assert_eq!(memory.registers.get(Register::R(2)), Word::CHAR('\u{12c}'));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(0x1F600));
+    // 0xD800 is a surrogate, not a scalar value
+    assert_eq!(memory.stack_size, 0);
+    assert!(Word::U64(0xD800).to_char().is_none());
+}
+
+#[test]
+fn test_stack_manipulation() {
+    let mut memory = Memory::build();
+    memory.push(Word::U64(1));
+    memory.push(Word::U64(2));
+    memory.push(Word::U64(3));
+    // 1 2 3 -> 2 3 1
+    assert!(memory.roll(2).is_ok());
+    assert!(memory.pick(1).is_ok());
+    assert_eq!(memory.stack_size, 4);
+    assert_eq!(memory.pop().unwrap(), Word::U64(3));
+    assert_eq!(memory.pop().unwrap(), Word::U64(1));
+    assert!(memory.pick(2).is_err());
+    assert!(memory.drop(3).is_err());
+
+    let vulkyn = run_src("
+        push 1
+        push 2
+        over
+        swap
+        dup
+        rot
+        dropn 1
+        exit
+    ");
+    // 1 2 -> 1 2 1 -> 1 1 2 -> 1 1 2 2 -> 1 2 2 1 -> 1 2 2
+    let memory = vulkyn.memory();
+    assert_eq!(memory.stack_size, 3);
+    assert_eq!(memory.stack_read(Word::U64(0)).unwrap(), Word::U64(1));
+    assert_eq!(memory.stack_read(Word::U64(2)).unwrap(), Word::U64(2));
+}
+
+#[test]
+fn test_label_values() {
+    let vulkyn = run_src("
+        rwrite %second r1
+        push %first
+        sgo
+        exit
+        %first
+        rwrite 1 r2
+        rgo r1
+        exit
+        %second
+        rwrite 2 r3
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(1));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(2));
+}
+
+#[test]
+fn test_switch() {
+    let src = "
+        .table %states [%zero %one]
+        switch %states %default
+        %zero
+        rwrite 0 r1
+        exit
+        %one
+        rwrite 1 r1
+        exit
+        %default
+        rwrite 2 r1
+        exit
+    ";
+    for (index,expected) in [(0,0),(1,1),(7,2)] {
+        let vulkyn = run_src(&format!("push {}\n{}",index,src));
+        assert_eq!(vulkyn.memory().registers.get(Register::R(1)), Word::U64(expected));
+    }
+}
+
+#[test]
+fn test_compare_and_branch() {
+    let vulkyn = run_src("
+        rwrite 5 r1
+        rwrite 0 r2
+        %loop
+        radd r2 2
+        smove r2
+        loop r1 %loop
+        golt r2 10 %less
+        goeq r2 10 %equal
+        exit
+        %less
+        exit
+        %equal
+        rwrite true r3
+        goz r1 %zero
+        exit
+        %zero
+        rwrite 7 r1
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(7));
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(10));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::BOOL(true));
+}
+
+#[test]
+fn test_destination_register() {
+    let vulkyn = run_src("
+        rwrite 4 r1
+        rwrite 6 r2
+        radd r3 r1 r2
+        rlt r2 r1 r3
+        rnot r1 r2
+        ru2f r2 r3
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(10));
+    assert_eq!(memory.registers.get(Register::R(1)), Word::BOOL(false));
+    assert_eq!(memory.registers.get(Register::R(2)), Word::F64(10.0));
+    assert_eq!(memory.stack_size, 0);
+}
+
+#[test]
+fn test_register_file() {
+    let vulkyn = run_src("
+        rwrite 1 r0
+        rwrite 4 r4
+        radd r31 r0 r4
+        push 7
+        rcopy ts r17
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(4)), Word::U64(4));
+    assert_eq!(memory.registers.get(Register::R(31)), Word::U64(5));
+    assert_eq!(memory.registers.get(Register::R(17)), Word::U64(0));
+    assert!(lexer::tokenize("push r32").is_err());
+}
+
+#[test]
+fn test_heap_addressing() {
+    let vulkyn = run_src("
+        alloc 3
+        smove r1
+        rwrite 5 r3
+        write r3 [r1 + 2]
+        push 9
+        write [r1 + r2*2 + 1]
+        rwrite 1 r2
+        readu [r1 + r2 + 1] 1 0
+        readu [r1 + r2*1] 1 0
+        smove r4
+        smove r5
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(4)), Word::U64(9));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::U64(5));
+    assert_eq!(memory.stack_size, 0);
+}
+
+#[test]
+fn test_linear_memory() {
+    let vulkyn = run_src("
+        memgrow 16
+        smove r1
+        rwrite -2 r2
+        store16 r2 [r1 + 4]
+        load16u [r1 + 4]
+        smove r3
+        load16i [r1 + 4]
+        smove r4
+        endian big
+        push 258
+        store32 [r1 + 8]
+        load8u [r1 + 11]
+        smove r5
+        memgrow 8
+        pop
+        memsize
+        smove r6
+        load8u [r1 + 24]
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(0));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(0xfffe));
+    assert_eq!(memory.registers.get(Register::R(4)), Word::I64(-2));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::U64(2));
+    assert_eq!(memory.registers.get(Register::R(6)), Word::U64(24));
+    assert_eq!(&memory.mem_bytes()[8..12], &[0, 0, 1, 2]);
+    assert_eq!(memory.registers.Fl, Word::U64(1 << 4));
+
+    let mut memory = Memory::build();
+    assert_eq!(memory.mem_grow(8), 0);
+    assert!(memory.store_bytes(0, 8, Word::F64(1.5)).is_ok());
+    assert_eq!(memory.load_bytes(0, 8, false).unwrap(), Word::U64(1.5f64.to_bits() as usize));
+    assert!(memory.load_bytes(4, 8, false).is_err());
+}
+
+#[test]
+fn test_heap_bulk() {
+    let vulkyn = run_src("
+        alloc 4
+        smove r1
+        fill [r1] 7 4
+        write 1 [r1]
+        write 2 [r1 + 1]
+        copy [r1 + 1] [r1] 3
+        readu [r1] 4 0
+        smove r5
+        smove r4
+        smove r3
+        smove r2
+        compare [r1] [r1 + 1] 1
+        smove r6
+        compare [r1 + 2] [r1 + 1] 2
+        smove r7
+        copy [r1 + 2] [r1] 3
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(1));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(1));
+    assert_eq!(memory.registers.get(Register::R(4)), Word::U64(2));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::U64(7));
+    assert_eq!(memory.registers.get(Register::R(6)), Word::I64(0));
+    assert_eq!(memory.registers.get(Register::R(7)), Word::I64(1));
+    assert_eq!(memory.registers.Fl, Word::U64(1 << 4));
+}
+
+#[test]
+fn test_objects() {
+    let vulkyn = run_src("
+        vnew
+        smove r1
+        push r1
+        push 10
+        vpush
+        push r1
+        push 'a'
+        vpush
+        push r1
+        push 0
+        push 11
+        vset
+        push r1
+        push 0
+        vget
+        smove r2
+        push r1
+        vpop
+        smove r3
+        push r1
+        vlen
+        smove r4
+        mnew
+        smove r5
+        push r5
+        push 'k'
+        push 1.5
+        mset
+        push r5
+        push 'k'
+        mget
+        smove r6
+        push r5
+        push 'x'
+        mhas
+        smove r7
+        push r1
+        ofree
+        push r5
+        push 'x'
+        mget
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(11));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::CHAR('a'));
+    assert_eq!(memory.registers.get(Register::R(4)), Word::U64(1));
+    assert_eq!(memory.registers.get(Register::R(6)), Word::F64(1.5));
+    assert_eq!(memory.registers.get(Register::R(7)), Word::BOOL(false));
+    assert_eq!(memory.registers.Fl, Word::U64(1 << 4));
+    assert_eq!(memory.live_objects(), 1);
+}
+
+#[test]
+fn test_pointer() {
+    let vulkyn = run_checked("
+        alloc 2
+        smove r1
+        push r1
+        push 1
+        add
+        smove r2
+        write 5 [r2]
+        readu [r1 + 1] 1 0
+        smove r3
+        push r2
+        push r1
+        sub
+        smove r4
+        free [r1]
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert!(matches!(memory.registers.get(Register::R(2)), Word::PTR(_)));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(5));
+    assert_eq!(memory.registers.get(Register::R(4)), Word::I64(-1));
+    assert_eq!(memory.registers.Fl, Word::U64(1));
+
+    let forged = "
+        alloc 1
+        smove r1
+        rwrite 16 r2
+        readu [r2] 1 0
+        exit
+    ";
+    assert_eq!(run_src(forged).memory().registers.Fl, Word::U64(1));
+    assert_eq!(run_checked(forged).memory().registers.Fl, Word::U64(1 << 4));
+    let escaping = "
+        alloc 2
+        push 3
+        add
+        exit
+    ";
+    assert_eq!(run_checked(escaping).memory().registers.Fl, Word::U64(1 << 4));
+    let overrun = "
+        alloc 2
+        smove r1
+        readu [r1 + 1] 2 0
+        exit
+    ";
+    assert_eq!(run_checked(overrun).memory().registers.Fl, Word::U64(1 << 4));
+}
+
+#[test]
+fn test_integer_operations() {
+    let vulkyn = run_src("
+        rpopcnt r1 255
+        rclz r2 1
+        rctz r3 8
+        rrotl r4 1 63
+        rashr r5 -8 1
+        push 1
+        push -8
+        rsh
+        smove r6
+        rbnot r7 0
+        rneg r8 5
+        rmin r9 -3 2
+        rmax r10 -3 2
+        rabs r11 -7
+        push 3
+        push -2
+        rotr
+        smove r12
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(8));
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(63));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(3));
+    assert_eq!(memory.registers.get(Register::R(4)), Word::U64(1 << 63));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::I64(-4));
+    assert_eq!(memory.registers.get(Register::R(6)), Word::U64((-8isize as usize) >> 1));
+    assert_eq!(memory.registers.get(Register::R(7)), Word::U64(usize::MAX));
+    assert_eq!(memory.registers.get(Register::R(8)), Word::U64(5usize.wrapping_neg()));
+    assert_eq!(memory.registers.get(Register::R(9)), Word::I64(-3));
+    assert_eq!(memory.registers.get(Register::R(10)), Word::U64(2));
+    assert_eq!(memory.registers.get(Register::R(11)), Word::I64(7));
+    assert_eq!(memory.registers.get(Register::R(12)), Word::I64((-2isize).rotate_right(3)));
+}
+
+#[test]
+fn test_calling_convention() {
+    let vulkyn = run_src("
+        rwrite 7 r16
+        push 3
+        push 4
+        callp %addmul 2
+        smove r2
+        smove r1
+        push 5
+        push 0
+        callp %sum 2
+        smove r3
+        exit
+        %addmul
+        pushr r16
+        load [li | 1]
+        load [li | 2]
+        add
+        load [li | 1]
+        load [li | 2]
+        mul
+        rwrite 0 r16
+        popr r16
+        ret 2
+        %sum
+        load [li | 1]
+        smove r4
+        goz r4 %sum_done
+        rsub r4 1
+        load [li | 2]
+        push r4
+        add
+        tailcall %sum 2
+        %sum_done
+        load [li | 2]
+        ret 1
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(7));
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(12));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(15));
+    assert_eq!(memory.registers.get(Register::R(16)), Word::U64(7));
+    assert_eq!(memory.registers.Li, Word::U64(0));
+    assert_eq!(memory.stack_size, 0);
+}
+
+#[test]
+fn test_coroutines() {
+    let vulkyn = run_src("
+        push 10
+        spawn %counter 1
+        smove r1
+        resume r1
+        smove r2
+        resume r1
+        smove r3
+        status r1
+        smove r4
+        resume r1
+        smove r5
+        status r1
+        smove r6
+        resume r1
+        exit
+        %counter
+        smove r1
+        yield r1
+        radd r1 1
+        yield
+        push 99
+        ret 1
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(1));
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(10));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(11));
+    assert_eq!(memory.registers.get(Register::R(4)), Word::U64(0));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::U64(99));
+    assert_eq!(memory.registers.get(Register::R(6)), Word::U64(2));
+    assert_eq!(memory.registers.Fl, Word::U64(1 << 3));
+    assert_eq!(memory.stack_size, 0);
+}
+
+fn load_src(src : &str) -> Vulkyn {
+    let tokens = lexer::tokenize(src).unwrap();
+    let instructions = Parser::init(tokens).run().unwrap();
+    Vulkyn::load(Program { instructions })
+}
+
+#[test]
+fn test_scheduler_channels() {
+    let scheduler = Scheduler::build(2);
+    scheduler.spawn(load_src("
+        chan_new 1
+        smove r2
+        push r2
+        spawn_vm %producer 1
+        smove r3
+        rwrite 0 r1
+        rwrite 4 r4
+        %loop
+        goz r4 %done
+        push r2
+        recv
+        smove r5
+        radd r1 r5
+        smove r1
+        rsub r4 1
+        smove r4
+        go %loop
+        %done
+        exit
+        %producer
+        smove r2
+        rwrite 4 r4
+        %produce
+        goz r4 %produced
+        push r2
+        push r4
+        send
+        rsub r4 1
+        smove r4
+        go %produce
+        %produced
+        exit
+    "));
+    let results = scheduler.run();
+    assert_eq!(results.len(), 2);
+    let memory = results[0].memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(10));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(1));
+    assert_eq!(memory.registers.Fl, Word::U64(1));
+    assert_eq!(results[1].id(), 1);
+    assert_eq!(results[1].memory().registers.Fl, Word::U64(1));
+}
+
+#[test]
+fn test_scheduler_deadlock() {
+    let scheduler = Scheduler::build(2);
+    scheduler.spawn(load_src("
+        chan_new
+        smove r2
+        push r2
+        spawn_vm %child 1
+        push r2
+        recv
+        exit
+        %child
+        recv
+        exit
+    "));
+    let results = scheduler.run();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].memory().registers.Fl, Word::U64(1 << 7));
+    assert_eq!(results[1].memory().registers.Fl, Word::U64(1 << 7));
+    // a lone vm never gets its value
+    let vulkyn = run_src("
+        chan_new
+        try_recv
+        smove r1
+        chan_new
+        recv
+        exit
+    ");
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::BOOL(false));
+    assert_eq!(memory.registers.Fl, Word::U64(1 << 7));
+    // spawn_vm needs a scheduler
+    let vulkyn = run_src("
+        spawn_vm %child
+        exit
+        %child
+        exit
+    ");
+    assert_eq!(vulkyn.memory().registers.Fl, Word::U64(1 << 3));
+}
+
+#[test]
+fn test_timer_interrupt() {
+    let vulkyn = run_src("
+        memgrow 8
+        pop
+        vector 0 %tick
+        timer 4 0
+        rwrite 20 r2
+        %loop
+        loop r2 %loop
+        timer 0 0
+        load64u [r0]
+        smove r1
+        exit
+        %tick
+        load64u [r0]
+        push 1
+        add
+        store64 [r0]
+        iret
+    ");
+    let memory = vulkyn.memory();
+    assert!(memory.registers.get(Register::R(1)).as_usize() > 0);
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(0));
+    assert_eq!(memory.registers.Fl, Word::U64(1));
+    assert_eq!(memory.stack_size, 0);
+}
+
+#[test]
+fn test_interrupt_mask() {
+    let mut vulkyn = load_src("
+        vector 3 %three
+        smove r6
+        memgrow 8
+        pop
+        rwrite 5 r5
+        vector 1 %one
+        vector 2 %two
+        imask
+        raise 2
+        raise 1
+        load64u [r0]
+        smove r1
+        iunmask 2
+        load64u [r0]
+        smove r2
+        iunmask
+        load64u [r0]
+        smove r3
+        imask
+        raise 2
+        raise 1
+        iunmask
+        load64u [r0]
+        smove r4
+        iret
+        %one
+        rwrite 0 r5
+        load64u [r0]
+        push 10
+        mul
+        push 1
+        add
+        store64 [r0]
+        iret
+        %two
+        load64u [r0]
+        push 10
+        mul
+        push 2
+        add
+        store64 [r0]
+        iret
+        %three
+        push 3
+        iret
+    ");
+    vulkyn.interrupt_line().raise(3);
+    vulkyn.exec();
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(0));
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(2));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::U64(21));
+    assert_eq!(memory.registers.get(Register::R(4)), Word::U64(2112));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::U64(5));
+    assert_eq!(memory.registers.get(Register::R(6)), Word::U64(3));
+    // iret outside of a handler
+    assert_eq!(memory.registers.Fl, Word::U64(1 << 3));
+}
+
+// Console output kept for the test
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_devices() {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut vulkyn = load_src("
+        rwrite 4294901760 r1
+        readu [r1] 1 0
+        write [r1]
+        readu [r1] 1 0
+        write [r1]
+        readu [r1] 1 0
+        smove r3
+        push 42
+        write [r1 | 1]
+        rwrite 4294905856 r4
+        readu [r4] 1 0
+        smove r5
+        write 1 [r4]
+        exit
+    ");
+    let console = Console::with(Box::new(Cursor::new("hé")), Box::new(Shared(output.clone())));
+    let memory = vulkyn.memory_mut();
+    assert!(memory.map_device(CONSOLE_BASE, Box::new(console)).is_ok());
+    assert!(memory.map_device(CLOCK_BASE, Box::new(Clock::build())).is_ok());
+    assert!(memory.map_device(CLOCK_BASE + 32, Box::new(Clock::build())).is_err());
+    vulkyn.exec();
+    let memory = vulkyn.memory();
+    assert_eq!(String::from_utf8(output.lock().unwrap().clone()).unwrap(), "hé42");
+    assert_eq!(memory.registers.get(Register::R(3)), Word::I64(-1));
+    assert!(memory.registers.get(Register::R(5)).as_usize() > 0);
+    // the clock is read only
+    assert_eq!(memory.registers.Fl, Word::U64(1 << 4));
+
+    // allocations skip mapped ranges
+    let mut memory = Memory::build();
+    assert!(memory.map_device(32, Box::new(Clock::build())).is_ok());
+    assert_eq!(memory.alloc(2).unwrap(), Word::PTR(80));
+}
+
+#[test]
+fn test_framebuffer() {
+    let dir = std::env::temp_dir().join(format!("vulkyn_frames_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut vulkyn = load_src("
+        clear 255
+        push 1
+        push 0
+        push 16711680
+        pixel
+        present
+        push 3
+        push 1
+        push 65280
+        pixel
+        present
+        push 4
+        push 0
+        push 0
+        pixel
+        exit
+    ");
+    vulkyn.set_framebuffer(Framebuffer::build(4, 2).with_output(dir.join("frame"), FrameFormat::Ppm));
+    vulkyn.exec();
+    let framebuffer = vulkyn.framebuffer().unwrap();
+    assert_eq!(framebuffer.frames(), 2);
+    assert_eq!(framebuffer.pixel(0, 0), Some(0x0000ff));
+    assert_eq!(framebuffer.pixel(1, 0), Some(0xff0000));
+    assert_eq!(framebuffer.pixel(3, 1), Some(0x00ff00));
+    // out of the frame
+    assert_eq!(vulkyn.memory().registers.Fl, Word::U64(1 << 4));
+    let first = std::fs::read(dir.join("frame0000.ppm")).unwrap();
+    assert!(first.starts_with(b"P6\n4 2\n255\n"));
+    assert_eq!(&first[11..17], &[0, 0, 255, 255, 0, 0]);
+    assert_eq!(std::fs::read(dir.join("frame0001.ppm")).unwrap(), framebuffer.ppm());
+    let png = framebuffer.png();
+    assert!(png.starts_with(&[0x89, b'P', b'N', b'G']));
+    assert!(png.ends_with(&[0xAE, 0x42, 0x60, 0x82]));
+    std::fs::remove_dir_all(&dir).unwrap();
+    // no framebuffer
+    let vulkyn = run_src("
+        present
+        exit
+    ");
+    assert_eq!(vulkyn.memory().registers.Fl, Word::U64(1 << 3));
+}
+
+#[test]
+fn test_files() {
+    let dir = std::env::temp_dir().join(format!("vulkyn_files_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("in"), "abc").unwrap();
+    let src = "
+        alloc 2
+        smove r1
+        write 'i' [r1]
+        write 'n' [r1 | 1]
+        push r1
+        push 2
+        fopen 0
+        smove r2
+        alloc 8
+        smove r3
+        push r2
+        push r3
+        push 8
+        fread
+        smove r4
+        readu [r3] 1 0
+        smove r10
+        write 'o' [r1]
+        write 'u' [r1 | 1]
+        push r1
+        push 2
+        fopen 1
+        smove r5
+        push r5
+        push r3
+        push 3
+        fwrite
+        smove r6
+        push r2
+        push -1
+        fseek
+        smove r7
+        push r2
+        fclose
+        pop
+        push r2
+        fclose
+        smove r8
+        write '.' [r1]
+        write '.' [r1 | 1]
+        push r1
+        push 2
+        fopen 0
+        smove r9
+        exit
+    ";
+    let tokens = lexer::tokenize(src).unwrap();
+    let instructions = Parser::init(tokens).run().unwrap();
+    let mut vulkyn = Vulkyn::load(Program { instructions : instructions.clone() });
+    vulkyn.grant_files(&dir, true).unwrap();
+    vulkyn.exec();
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(2)), Word::I64(1));
+    assert_eq!(memory.registers.get(Register::R(4)), Word::I64(3));
+    assert_eq!(memory.registers.get(Register::R(10)), Word::U64(97));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::I64(2));
+    assert_eq!(memory.registers.get(Register::R(6)), Word::I64(3));
+    assert_eq!(memory.registers.get(Register::R(7)), Word::I64(2));
+    assert_eq!(memory.registers.get(Register::R(8)), Word::I64(-3));
+    // outside of the root
+    assert_eq!(memory.registers.get(Register::R(9)), Word::I64(-1));
+    assert_eq!(std::fs::read(dir.join("ou")).unwrap(), b"abc");
+    // read only
+    let mut vulkyn = Vulkyn::load(Program { instructions });
+    vulkyn.grant_files(&dir, false).unwrap();
+    vulkyn.exec();
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(2)), Word::I64(1));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::I64(-1));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_sockets() {
+    // client
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let echo = thread::spawn(move || {
+        let (mut stream,_) = listener.accept().unwrap();
+        let mut bytes = [0;2];
+        stream.read_exact(&mut bytes).unwrap();
+        stream.write_all(&bytes).unwrap();
+    });
+    let mut vulkyn = load_src(&format!("
+        alloc 2
+        smove r1
+        write 'h' [r1]
+        write 'i' [r1 | 1]
+        push 2130706433
+        push {port}
+        connect
+        smove r2
+        push r2
+        push r1
+        push 2
+        sock_send
+        smove r3
+        alloc 4
+        smove r4
+        push r2
+        push r4
+        push 4
+        sock_recv
+        smove r5
+        readu [r4 | 1] 1 0
+        smove r6
+        push r2
+        sock_close
+        pop
+        push 167772161
+        push 80
+        connect
+        smove r7
+        push r2
+        sock_close
+        smove r8
+        exit
+    "));
+    vulkyn.grant_net(vec![Ipv4Addr::LOCALHOST]);
+    vulkyn.exec();
+    echo.join().unwrap();
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(2)), Word::I64(1));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::I64(2));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::I64(2));
+    assert_eq!(memory.registers.get(Register::R(6)), Word::U64('i' as usize));
+    // host not granted
+    assert_eq!(memory.registers.get(Register::R(7)), Word::I64(-1));
+    assert_eq!(memory.registers.get(Register::R(8)), Word::I64(-3));
+
+    // echo server
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let mut vulkyn = load_src(&format!("
+        push 2130706433
+        push {port}
+        listen
+        smove r1
+        push r1
+        accept
+        smove r2
+        alloc 4
+        smove r3
+        push r2
+        push r3
+        push 4
+        sock_recv
+        smove r4
+        push r2
+        push r3
+        push r4
+        sock_send
+        pop
+        push r1
+        sock_port
+        smove r5
+        exit
+    "));
+    vulkyn.grant_net(vec![Ipv4Addr::LOCALHOST]);
+    let server = thread::spawn(move || {
+        vulkyn.exec();
+        vulkyn
+    });
+    let mut stream = loop {
+        if let Ok(stream) = TcpStream::connect(("127.0.0.1", port)) {
+            break stream;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    stream.write_all(b"yo").unwrap();
+    let mut bytes = [0;2];
+    stream.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"yo");
+    let vulkyn = server.join().unwrap();
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(4)), Word::I64(2));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::I64(port as isize));
+    // without grant
+    let vulkyn = run_src("
+        push 2130706433
+        push 0
+        listen
+        smove r1
+        exit
+    ");
+    assert_eq!(vulkyn.memory().registers.get(Register::R(1)), Word::I64(-1));
+}
+
+#[test]
+fn test_args_env() {
+    let mut vulkyn = load_src("
+        argc
+        smove r1
+        argv 1
+        smove r3
+        smove r2
+        readu [r2 | 2] 1 0
+        smove r4
+        push 5
+        argv
+        smove r6
+        smove r5
+        alloc 4
+        smove r7
+        write 'H' [r7]
+        write 'O' [r7 | 1]
+        write 'M' [r7 | 2]
+        write 'E' [r7 | 3]
+        push r7
+        push 4
+        getenv
+        smove r9
+        smove r8
+        push r7
+        push 3
+        getenv
+        smove r10
+        pop
+        exit
+    ");
+    vulkyn.set_args(vec!["run".to_owned(), "héllo".to_owned()]);
+    vulkyn.set_env("HOME", "/home/vk");
+    vulkyn.exec();
+    let memory = vulkyn.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(2));
+    assert_eq!(memory.registers.get(Register::R(3)), Word::I64(5));
+    assert_eq!(memory.registers.get(Register::R(4)), Word::CHAR('l'));
+    assert_eq!(memory.registers.get(Register::R(5)), Word::U64(0));
+    assert_eq!(memory.registers.get(Register::R(6)), Word::I64(-1));
+    assert_eq!(memory.registers.get(Register::R(9)), Word::I64(8));
+    let home = memory.registers.get(Register::R(8));
+    assert_eq!(vulkyn.memory_mut().read(home, 1, 1).unwrap(), vec![Word::CHAR('h')]);
+    // HOM is not exposed
+    assert_eq!(vulkyn.memory().registers.get(Register::R(10)), Word::I64(-1));
+}
+
+#[test]
+fn test_random_clock() {
+    let src = "
+        clock
+        smove r1
+        sleep 1000
+        clock
+        smove r2
+        random
+        smove r3
+        random 6
+        smove r4
+        randf
+        smove r5
+        seed 42
+        random
+        smove r6
+        push 42
+        seed
+        random
+        smove r7
+        exit
+    ";
+    let first = run_src(src);
+    let second = run_src(src);
+    let memory = first.memory();
+    assert_eq!(memory.registers.get(Register::R(1)), Word::U64(0));
+    assert_eq!(memory.registers.get(Register::R(2)), Word::U64(1003));
+    assert!(memory.registers.get(Register::R(4)).as_usize() < 6);
+    let Word::F64(randf) = memory.registers.get(Register::R(5)) else {
+        panic!("randf pushes a F64");
+    };
+    assert!((0.0..1.0).contains(&randf));
+    assert_ne!(memory.registers.get(Register::R(3)), memory.registers.get(Register::R(6)));
+    assert_eq!(memory.registers.get(Register::R(6)), memory.registers.get(Register::R(7)));
+    // a run is reproducible
+    for i in 1..8 {
+        assert_eq!(memory.registers.get(Register::R(i)), second.memory().registers.get(Register::R(i)));
+    }
+    let mut seeded = load_src(src);
+    seeded.set_seed(7);
+    seeded.exec();
+    assert_ne!(seeded.memory().registers.get(Register::R(3)), memory.registers.get(Register::R(3)));
+
+    let mut vulkyn = load_src("
+        sleep 1000000
+        clock
+        smove r1
+        exit
+    ");
+    vulkyn.set_wall_clock(true);
+    vulkyn.exec();
+    assert!(vulkyn.memory().registers.get(Register::R(1)).as_usize() >= 1000000);
+}
@@ -1,9 +1,9 @@
-use std::{path::PathBuf, fs::File};
+use std::{path::{Path, PathBuf}, fs::File, collections::HashMap, sync::Arc, io, net::Ipv4Addr, time::{Duration, Instant}, thread};
 
 use serde::{Serialize, Deserialize};
-use crate::vm::memory::{Memory};
+use crate::vm::memory::{Memory, Endian, Object, Context};
 
-use super::{word::Word, register::{Register}};
+use super::{word::Word, register::{Register, Registers}, scheduler::{Hub, ChannelError}, interrupt::{InterruptLine, INTERRUPTS}, framebuffer::Framebuffer, files::{Files, FileMode}, net::{Net, NET_INVALID}, random::Random};
 
 #[derive(Debug,Clone,Serialize,Deserialize)]
 pub struct Program{
@@ -16,6 +16,16 @@ pub enum Either<L, R> {
     Right(R),
 }
 
+// Address operand : [base + index*scale + displacement]
+// index and displacement count words on the heap, slots on the stack
+// and bytes in the linear memory
+#[derive(Debug,Clone,Copy,Serialize,Deserialize)]
+pub struct Address {
+    pub base : Register,
+    pub index : Option<(Register,usize)>,
+    pub displacement : isize,
+}
+
 
 
 #[derive(Debug,Clone,Serialize,Deserialize)]
@@ -23,22 +33,67 @@ pub enum Instruction {
     /* MEMORY ACCESS */
     PUSH(Either<Word,Register>),
     POP,
+    DUP,
+    SWAP,
+    OVER,
+    ROT,
+    PICK(usize),
+    ROLL(usize),
+    DROPN(usize),
     SCOPY(Register),
     SMOVE(Register),
     RCOPY(Register,Register),
     RMOVE(Register,Register),
     RWRITE(Word,Register),
-    LOAD((Register,isize)),
-    LOADB((Register,isize),usize),
-    READU((Register,isize),usize,usize),
-    READD((Register,isize),usize,usize),
+    LOAD(Address),
+    LOADB(Address,usize),
+    READU(Address,usize,usize),
+    READD(Address,usize,usize),
     SREADU(usize,usize),
     SREADD(usize,usize),
-    WRITE(Word,(Register,isize)),
+    // None : the value is popped from the stack
+    WRITE(Option<Either<Word,Register>>,Address),
     SWRITE,
     ALLOC(usize),
-    FREE((Register,isize)),
+    FREE(Address),
     SFREE,
+    // destination, source, number of words
+    COPY(Address,Address,Either<Word,Register>),
+    // destination, value, number of words
+    FILL(Address,Either<Word,Register>,Either<Word,Register>),
+    // push -1, 0 or 1 as an I64
+    COMPARE(Address,Address,Either<Word,Register>),
+
+    /* LINEAR MEMORY */
+    // true : the loaded value is sign extended to an I64
+    LOAD8(Address,bool),
+    LOAD16(Address,bool),
+    LOAD32(Address,bool),
+    LOAD64(Address,bool),
+    // None : the value is popped from the stack
+    STORE8(Option<Either<Word,Register>>,Address),
+    STORE16(Option<Either<Word,Register>>,Address),
+    STORE32(Option<Either<Word,Register>>,Address),
+    STORE64(Option<Either<Word,Register>>,Address),
+    MEMSIZE,
+    MEMGROW(usize),
+    ENDIAN(Endian),
+
+    /* OBJECTS */
+    // operands are popped, the handle first pushed
+    VNEW,
+    VPUSH,
+    VPOP,
+    VGET,
+    VSET,
+    VLEN,
+    MNEW,
+    MSET,
+    MGET,
+    MHAS,
+    MDEL,
+    MLEN,
+    OFREE,
 
     /* OPERATOR */
     // +
@@ -72,6 +127,26 @@ pub enum Instruction {
     // <<
     LSHIFT,
     RLSHIFT(Either<Word,Register>,Either<Word,Register>),
+    // >> keeping the sign
+    ASHR,
+    RASHR(Either<Word,Register>,Either<Word,Register>),
+    ROTL,
+    RROTL(Either<Word,Register>,Either<Word,Register>),
+    ROTR,
+    RROTR(Either<Word,Register>,Either<Word,Register>),
+    // ~
+    BNOT,
+    RBNOT(Either<Word,Register>),
+    // number of ones, leading zeros and trailing zeros
+    POPCNT,
+    RPOPCNT(Either<Word,Register>),
+    CLZ,
+    RCLZ(Either<Word,Register>),
+    CTZ,
+    RCTZ(Either<Word,Register>),
+    // two's complement -x
+    NEG,
+    RNEG(Either<Word,Register>),
 
 
     // == 
@@ -103,6 +178,43 @@ pub enum Instruction {
     EGREAT,
     REGREAT(Either<Word,Register>,Either<Word,Register>),
 
+    // R-form operation writing its result into the register instead of the stack
+    RDEST(Register,Box<Instruction>),
+
+    /* FLOAT MATH */
+    SQRT,
+    RSQRT(Either<Word,Register>),
+    POW,
+    RPOW(Either<Word,Register>,Either<Word,Register>),
+    EXP,
+    REXP(Either<Word,Register>),
+    LN,
+    RLN(Either<Word,Register>),
+    SIN,
+    RSIN(Either<Word,Register>),
+    COS,
+    RCOS(Either<Word,Register>),
+    TAN,
+    RTAN(Either<Word,Register>),
+    ATAN2,
+    RATAN2(Either<Word,Register>,Either<Word,Register>),
+    FLOOR,
+    RFLOOR(Either<Word,Register>),
+    CEIL,
+    RCEIL(Either<Word,Register>),
+    ROUND,
+    RROUND(Either<Word,Register>),
+    ABS,
+    RABS(Either<Word,Register>),
+    MIN,
+    RMIN(Either<Word,Register>,Either<Word,Register>),
+    MAX,
+    RMAX(Either<Word,Register>,Either<Word,Register>),
+    ISNAN,
+    RISNAN(Either<Word,Register>),
+    ISINF,
+    RISINF(Either<Word,Register>),
+
     // Conversion
     F2I,
     F2U,
@@ -158,15 +270,123 @@ pub enum Instruction {
     NOP,
     LABEL,
     GO(usize),
+    RGO(Register),
+    SGO,
+    // jump table and default address
+    SWITCH(Vec<usize>,usize),
     GOIF(usize),
     RGOIF(usize,Register),
+    // compare two operands and branch
+    GOEQ(Either<Word,Register>,Either<Word,Register>,usize),
+    GONE(Either<Word,Register>,Either<Word,Register>,usize),
+    GOLT(Either<Word,Register>,Either<Word,Register>,usize),
+    GOLE(Either<Word,Register>,Either<Word,Register>,usize),
+    GOGT(Either<Word,Register>,Either<Word,Register>,usize),
+    GOGE(Either<Word,Register>,Either<Word,Register>,usize),
+    GOZ(Either<Word,Register>,usize),
+    GONZ(Either<Word,Register>,usize),
+    // decrement the register and branch while it is not zero
+    LOOP(Register,usize),
+    // branch on the condition flags of He
+    GOFZ(usize),
+    GOFNZ(usize),
+    GOFN(usize),
+    GOFNN(usize),
+    GOFC(usize),
+    GOFNC(usize),
+    GOFV(usize),
+    GOFNV(usize),
     CALL(usize),
     SCALL,
     CALLP(usize,usize),
     SCALLP(usize),
     RCALL(Register),
     RCALLP(Register,usize),
+    // reuse the current frame, keeping the top n words as arguments
+    TAILCALL(usize,usize),
     RET(usize),
+    // save and restore registers, POPR restores the list in reverse order
+    PUSHR(Vec<Register>),
+    POPR(Vec<Register>),
+
+    /* COROUTINES */
+    // entry label and number of words moved to the new stack
+    SPAWN(usize,usize),
+    // None : the value or handle is popped from the stack
+    YIELD(Option<Either<Word,Register>>),
+    RESUME(Option<Either<Word,Register>>),
+    STATUS(Option<Either<Word,Register>>),
+
+    /* CHANNELS */
+    // capacity, 0 for an unbounded channel
+    CHANNEW(usize),
+    // stack : channel value
+    SEND,
+    TRYSEND,
+    // stack : channel
+    RECV,
+    TRYRECV,
+    // entry label and number of words moved to the new vm stack
+    SPAWNVM(usize,usize),
+
+    /* INTERRUPTS */
+    // interrupt number and handler label
+    VECTOR(usize,usize),
+    RAISE(usize),
+    IRET,
+    // period in instructions, 0 to stop the timer, and interrupt number
+    TIMER(usize,usize),
+    // interrupt number, every interrupt when omitted
+    IMASK(Option<usize>),
+    IUNMASK(Option<usize>),
+
+    /* FRAMEBUFFER */
+    // stack : x y color
+    PIXEL,
+    // color, popped when omitted
+    CLEAR(Option<Either<Word,Register>>),
+    PRESENT,
+
+    /* FILES */
+    // stack : path_address path_length
+    FOPEN(FileMode),
+    // stack : handle address count
+    FREAD,
+    FWRITE,
+    // stack : handle offset
+    FSEEK,
+    // stack : handle
+    FCLOSE,
+
+    /* SOCKETS */
+    // stack : ipv4_host port
+    LISTEN,
+    CONNECT,
+    // stack : handle
+    ACCEPT,
+    // stack : handle address count
+    SOCKSEND,
+    SOCKRECV,
+    // stack : handle
+    SOCKPORT,
+    SOCKCLOSE,
+
+    /* PROGRAM ARGUMENTS */
+    ARGC,
+    // index, popped when omitted
+    ARGV(Option<Either<Word,Register>>),
+    // stack : name_address name_length
+    GETENV,
+
+    /* RANDOM AND TIME */
+    // bound, the whole u64 range when omitted
+    RANDOM(Option<Either<Word,Register>>),
+    RANDF,
+    // seed, popped when omitted
+    SEED(Option<Either<Word,Register>>),
+    CLOCK,
+    // nanoseconds, popped when omitted
+    SLEEP(Option<Either<Word,Register>>),
 }
 #[derive(Debug)]
 pub enum State {
@@ -176,6 +396,11 @@ pub enum State {
     IllegalInstruction,
     SegmentationFault,
     DivisionZero,
+    InvalidConversion,
+    Deadlock,
+    IOError,
+    // waiting on a channel, the instruction is run again later
+    Blocked,
 }
 
 const FLAG_OK: Word = Word::U64(0x1 << 0);
@@ -184,6 +409,15 @@ const FLAG_ST_UF: Word =   Word::U64(0x1 << 2);
 const FLAG_I_I: Word =  Word::U64(0x1  << 3);
 const FLAG_SF: Word =  Word::U64(0x1  << 4);
 const FLAG_DZ: Word =  Word::U64(0x1  << 5);
+const FLAG_IC: Word =  Word::U64(0x1  << 6);
+const FLAG_DL: Word =  Word::U64(0x1  << 7);
+const FLAG_IO: Word =  Word::U64(0x1  << 8);
+
+/* He : condition flags set by arithmetic and comparison */
+pub const COND_ZERO: Word = Word::U64(0x1 << 0);
+pub const COND_NEGATIVE: Word = Word::U64(0x1 << 1);
+pub const COND_CARRY: Word = Word::U64(0x1 << 2);
+pub const COND_OVERFLOW: Word = Word::U64(0x1 << 3);
 
 impl State {
     fn flag(&self) -> Word{
@@ -194,13 +428,64 @@ impl State {
             State::IllegalInstruction => FLAG_I_I,
             State::SegmentationFault => FLAG_SF,
             State::DivisionZero => FLAG_DZ,
+            State::InvalidConversion => FLAG_IC,
+            State::Deadlock => FLAG_DL,
+            State::IOError => FLAG_IO,
+            State::Blocked => FLAG_OK,
         }
     }
 }
 
+// Pushed by STATUS
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoroutineStatus {
+    Suspended = 0,
+    Running = 1,
+    Done = 2,
+}
+
+#[derive(Debug)]
+struct Coroutine {
+    context : Context,
+    status : CoroutineStatus,
+}
+
 pub struct Vulkyn {
     memory : Memory,
-    program : Program,
+    program : Arc<Program>,
+    // heap accesses require a pointer within its allocation
+    checked : bool,
+    // the handle of a coroutine is its index + 1
+    coroutines : Vec<Coroutine>,
+    // running coroutines, the last one being the current
+    resumed : Vec<usize>,
+    // handler label of every interrupt number
+    vectors : HashMap<usize,usize>,
+    interrupts : InterruptLine,
+    // one bit per masked interrupt number
+    mask : u64,
+    // registers of the interrupted code, the last one is the interrupt being served
+    interrupted : Vec<Registers>,
+    // period and interrupt number of the virtual timer
+    timer : Option<(usize,usize)>,
+    ticks : usize,
+    framebuffer : Option<Framebuffer>,
+    files : Files,
+    net : Net,
+    // program arguments and environment variables exposed by the host
+    args : Vec<String>,
+    env : HashMap<String,String>,
+    random : Random,
+    // virtual time in nanoseconds, one per instruction run
+    time : usize,
+    // start of the wall clock when the host opted into it
+    wall_clock : Option<Instant>,
+    // channels, shared with the other vms once attached to a scheduler
+    hub : Arc<Hub>,
+    scheduled : bool,
+    id : usize,
+    // progress count of the hub when the vm blocked on a channel
+    blocked_at : Option<u64>,
 }
 
 impl Vulkyn {
@@ -215,10 +500,121 @@ impl Vulkyn {
         }
         let program = program.unwrap();
 
-        Ok(Self {
+        Ok(Self::load(program))
+    }
+
+    pub fn load(program : Program) -> Self {
+        Self {
             memory:Memory::build(),
-            program : program,
-        })
+            program : Arc::new(program),
+            checked : false,
+            coroutines : Vec::new(),
+            resumed : Vec::new(),
+            vectors : HashMap::new(),
+            interrupts : InterruptLine::build(),
+            mask : 0,
+            interrupted : Vec::new(),
+            timer : None,
+            ticks : 0,
+            framebuffer : None,
+            files : Files::build(),
+            net : Net::build(),
+            args : Vec::new(),
+            env : HashMap::new(),
+            random : Random::build(0),
+            time : 0,
+            wall_clock : None,
+            hub : Arc::new(Hub::build()),
+            scheduled : false,
+            id : 0,
+            blocked_at : None,
+        }
+    }
+
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    pub fn memory_mut(&mut self) -> &mut Memory {
+        &mut self.memory
+    }
+
+    pub fn set_checked(&mut self,checked : bool) {
+        self.checked = checked;
+    }
+
+    // Handle to raise interrupts while the vm runs
+    pub fn interrupt_line(&self) -> InterruptLine {
+        self.interrupts.clone()
+    }
+
+    pub fn raise(&self,number : usize) {
+        self.interrupts.raise(number);
+    }
+
+    pub fn set_framebuffer(&mut self,framebuffer : Framebuffer) {
+        self.framebuffer = Some(framebuffer);
+    }
+
+    pub fn framebuffer(&self) -> Option<&Framebuffer> {
+        self.framebuffer.as_ref()
+    }
+
+    // Confine the file instructions to root, read only unless writable
+    pub fn grant_files(&mut self,root : &Path,writable : bool) -> io::Result<()> {
+        self.files.grant(root, writable)
+    }
+
+    pub fn set_args(&mut self,args : Vec<String>) {
+        self.args = args;
+    }
+
+    // Only the variables set here are visible to GETENV
+    pub fn set_env(&mut self,name : &str,value : &str) {
+        self.env.insert(name.to_owned(), value.to_owned());
+    }
+
+    pub fn set_seed(&mut self,seed : u64) {
+        self.random.seed(seed);
+    }
+
+    // CLOCK and SLEEP use the real time instead of the virtual clock
+    pub fn set_wall_clock(&mut self,wall_clock : bool) {
+        self.wall_clock = if wall_clock { Some(Instant::now()) } else { None };
+    }
+
+    // Nanoseconds, virtual unless the host opted into the wall clock
+    pub fn clock(&self) -> usize {
+        match self.wall_clock {
+            Some(start) => start.elapsed().as_nanos() as usize,
+            None => self.time,
+        }
+    }
+
+    // Allow the socket instructions to listen on and connect to these hosts
+    pub fn grant_net(&mut self,hosts : Vec<Ipv4Addr>) {
+        self.net.grant(hosts);
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub(crate) fn set_id(&mut self,id : usize) {
+        self.id = id;
+    }
+
+    pub(crate) fn attach(&mut self,hub : Arc<Hub>) {
+        self.hub = hub;
+        self.scheduled = true;
+    }
+
+    pub(crate) fn blocked_at(&self) -> Option<u64> {
+        self.blocked_at
+    }
+
+    pub(crate) fn deadlock(&mut self) {
+        self.memory.registers.Fl = State::Deadlock.flag();
     }
 
     fn get_instruction(&self) -> Option<Instruction>{
@@ -229,6 +625,7 @@ impl Vulkyn {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let some_instruction = self.program.instructions.get(word);
@@ -246,22 +643,65 @@ impl Vulkyn {
         self.memory.registers.Ni = word;
     }
     pub fn exec(&mut self) {
+        if !self.slice() {
+            // no other vm can ever unblock a lone one
+            self.deadlock();
+        }
+        self.exit()
+    }
+    // Run until the program stops : true, or waits on a channel : false
+    pub(crate) fn slice(&mut self) -> bool {
         loop {
             if let Some(instruction) = self.get_instruction(){
                 let state = self.run(instruction);
-                self.next_instruction();
+                if let State::Blocked = state {
+                    return false;
+                }
+                self.blocked_at = None;
+                self.time += 1;
                 let flag = state.flag();
+                if flag & FLAG_OK == FLAG_OK {
+                    self.tick();
+                    self.interrupt();
+                }
+                self.next_instruction();
                 self.memory.registers.Fl = flag;
                 if flag & FLAG_OK != FLAG_OK {
-                    break;
+                    return true;
                 }
             }else {
-                break;
+                return true;
             }
         }
-        self.exit()
     }
 
+    // The timer counts the instructions run outside of interrupt handlers,
+    // a handler longer than the period can not starve the program
+    fn tick(&mut self) {
+        if !self.interrupted.is_empty() {
+            return;
+        }
+        if let Some((period,number)) = self.timer {
+            self.ticks += 1;
+            if self.ticks % period == 0 {
+                self.interrupts.raise(number);
+            }
+        }
+    }
+    // Jump to the handler of the lowest pending interrupt, they wait while
+    // another one is served. Interrupts without handler are dropped.
+    fn interrupt(&mut self) {
+        if !self.interrupted.is_empty() {
+            return;
+        }
+        while let Some(number) = self.interrupts.take(self.mask) {
+            if let Some(label) = self.vectors.get(&number) {
+                self.interrupted.push(self.memory.registers);
+                self.memory.registers.Ni = Word::U64(*label);
+                return;
+            }
+        }
+    }
     fn run(&mut self,instruction : Instruction) -> State {
         match instruction {
             Instruction::ADD | Instruction::MINUS | Instruction::MUL | Instruction::DIV | Instruction::MOD=> {
@@ -271,14 +711,14 @@ impl Vulkyn {
                 return self.bitewise_operation(instruction);
             }
             Instruction::RBAND(_,_) | Instruction::RBOR(_,_) | Instruction::RBXOR(_,_) | Instruction::RLSHIFT(_,_) | Instruction::RRSHIFT(_,_) => {
-                return self.r_bitewise_operation(instruction);
+                return self.r_bitewise_operation(instruction, None);
             }
             Instruction::RADD(_,_) 
                 | Instruction::RMINUS(_,_) 
                 | Instruction::RMUL(_,_) 
                 | Instruction::RDIV(_,_)
                 | Instruction::RMOD(_,_) => {
-                return self.r_operation(instruction);
+                return self.r_operation(instruction, None);
             }
             Instruction::AND 
             | Instruction::OR 
@@ -302,18 +742,213 @@ impl Vulkyn {
                 | Instruction::RDIFF(_,_)
                 | Instruction::RNOT(_)
                 => {
-                return self.r_boolean_operation(instruction);
+                return self.r_boolean_operation(instruction, None);
             },
+            Instruction::RDEST(reg,inner) => {
+                let dest = Some(reg);
+                return match *inner {
+                    inst @ (Instruction::RADD(..)
+                        | Instruction::RMINUS(..)
+                        | Instruction::RMUL(..)
+                        | Instruction::RDIV(..)
+                        | Instruction::RMOD(..)) => self.r_operation(inst, dest),
+                    inst @ (Instruction::RBAND(..)
+                        | Instruction::RBOR(..)
+                        | Instruction::RBXOR(..)
+                        | Instruction::RLSHIFT(..)
+                        | Instruction::RRSHIFT(..)) => self.r_bitewise_operation(inst, dest),
+                    inst @ (Instruction::RASHR(..)
+                        | Instruction::RROTL(..)
+                        | Instruction::RROTR(..)
+                        | Instruction::RBNOT(..)
+                        | Instruction::RPOPCNT(..)
+                        | Instruction::RCLZ(..)
+                        | Instruction::RCTZ(..)
+                        | Instruction::RNEG(..)) => self.r_integer_operation(inst, dest),
+                    inst @ (Instruction::RAND(..)
+                        | Instruction::ROR(..)
+                        | Instruction::RLESS(..)
+                        | Instruction::RGREAT(..)
+                        | Instruction::RELESS(..)
+                        | Instruction::REGREAT(..)
+                        | Instruction::REQUAL(..)
+                        | Instruction::RDIFF(..)
+                        | Instruction::RNOT(..)) => self.r_boolean_operation(inst, dest),
+                    inst @ (Instruction::RSQRT(..)
+                        | Instruction::RPOW(..)
+                        | Instruction::REXP(..)
+                        | Instruction::RLN(..)
+                        | Instruction::RSIN(..)
+                        | Instruction::RCOS(..)
+                        | Instruction::RTAN(..)
+                        | Instruction::RATAN2(..)
+                        | Instruction::RFLOOR(..)
+                        | Instruction::RCEIL(..)
+                        | Instruction::RROUND(..)
+                        | Instruction::RABS(..)
+                        | Instruction::RMIN(..)
+                        | Instruction::RMAX(..)
+                        | Instruction::RISNAN(..)
+                        | Instruction::RISINF(..)) => self.r_float_operation(inst, dest),
+                    inst @ (Instruction::RF2I(..)
+                        | Instruction::RF2U(..)
+                        | Instruction::RF2B(..)
+                        | Instruction::RF2C(..)
+                        | Instruction::RI2F(..)
+                        | Instruction::RI2U(..)
+                        | Instruction::RI2B(..)
+                        | Instruction::RI2C(..)
+                        | Instruction::RU2I(..)
+                        | Instruction::RU2F(..)
+                        | Instruction::RU2C(..)
+                        | Instruction::RU2B(..)
+                        | Instruction::RC2I(..)
+                        | Instruction::RC2F(..)
+                        | Instruction::RC2U(..)
+                        | Instruction::RC2B(..)
+                        | Instruction::RB2I(..)
+                        | Instruction::RB2F(..)
+                        | Instruction::RB2U(..)
+                        | Instruction::RB2C(..)) => self.conversion_operation(inst, dest),
+                    _ => State::IllegalInstruction
+                }
+            }
+            Instruction::ASHR
+                | Instruction::ROTL
+                | Instruction::ROTR
+                | Instruction::BNOT
+                | Instruction::POPCNT
+                | Instruction::CLZ
+                | Instruction::CTZ
+                | Instruction::NEG
+                => {
+                return self.integer_operation(instruction);
+            }
+            Instruction::RASHR(_,_)
+                | Instruction::RROTL(_,_)
+                | Instruction::RROTR(_,_)
+                | Instruction::RBNOT(_)
+                | Instruction::RPOPCNT(_)
+                | Instruction::RCLZ(_)
+                | Instruction::RCTZ(_)
+                | Instruction::RNEG(_)
+                => {
+                return self.r_integer_operation(instruction, None);
+            }
+            Instruction::SQRT
+                | Instruction::POW
+                | Instruction::EXP
+                | Instruction::LN
+                | Instruction::SIN
+                | Instruction::COS
+                | Instruction::TAN
+                | Instruction::ATAN2
+                | Instruction::FLOOR
+                | Instruction::CEIL
+                | Instruction::ROUND
+                | Instruction::ABS
+                | Instruction::MIN
+                | Instruction::MAX
+                | Instruction::ISNAN
+                | Instruction::ISINF
+                => {
+                return self.float_operation(instruction);
+            }
+            Instruction::RSQRT(_)
+                | Instruction::RPOW(_,_)
+                | Instruction::REXP(_)
+                | Instruction::RLN(_)
+                | Instruction::RSIN(_)
+                | Instruction::RCOS(_)
+                | Instruction::RTAN(_)
+                | Instruction::RATAN2(_,_)
+                | Instruction::RFLOOR(_)
+                | Instruction::RCEIL(_)
+                | Instruction::RROUND(_)
+                | Instruction::RABS(_)
+                | Instruction::RMIN(_,_)
+                | Instruction::RMAX(_,_)
+                | Instruction::RISNAN(_)
+                | Instruction::RISINF(_)
+                => {
+                return self.r_float_operation(instruction, None);
+            }
             Instruction::CALL(_)
                 | Instruction::CALLP(_, _)
                 | Instruction::SCALL
                 | Instruction::SCALLP(_)
                 | Instruction::RCALL(_)
                 | Instruction::RCALLP(_, _)
+                | Instruction::TAILCALL(_, _)
                 | Instruction::RET(_)
+                | Instruction::PUSHR(_)
+                | Instruction::POPR(_)
                 => {
                 return self.function_operation(instruction);
             },
+            Instruction::CHANNEW(_)
+                | Instruction::SEND
+                | Instruction::TRYSEND
+                | Instruction::RECV
+                | Instruction::TRYRECV
+                | Instruction::SPAWNVM(_, _)
+                => {
+                return self.channel_operation(instruction);
+            },
+            Instruction::RANDOM(_)
+                | Instruction::RANDF
+                | Instruction::SEED(_)
+                | Instruction::CLOCK
+                | Instruction::SLEEP(_)
+                => {
+                return self.time_operation(instruction);
+            },
+            Instruction::ARGC
+                | Instruction::ARGV(_)
+                | Instruction::GETENV
+                => {
+                return self.args_operation(instruction);
+            },
+            Instruction::LISTEN
+                | Instruction::CONNECT
+                | Instruction::ACCEPT
+                | Instruction::SOCKSEND
+                | Instruction::SOCKRECV
+                | Instruction::SOCKPORT
+                | Instruction::SOCKCLOSE
+                => {
+                return self.net_operation(instruction);
+            },
+            Instruction::FOPEN(_)
+                | Instruction::FREAD
+                | Instruction::FWRITE
+                | Instruction::FSEEK
+                | Instruction::FCLOSE
+                => {
+                return self.file_operation(instruction);
+            },
+            Instruction::PIXEL
+                | Instruction::CLEAR(_)
+                | Instruction::PRESENT
+                => {
+                return self.framebuffer_operation(instruction);
+            },
+            Instruction::VECTOR(_, _)
+                | Instruction::RAISE(_)
+                | Instruction::IRET
+                | Instruction::TIMER(_, _)
+                | Instruction::IMASK(_)
+                | Instruction::IUNMASK(_)
+                => {
+                return self.interrupt_operation(instruction);
+            },
+            Instruction::SPAWN(_, _)
+                | Instruction::YIELD(_)
+                | Instruction::RESUME(_)
+                | Instruction::STATUS(_)
+                => {
+                return self.coroutine_operation(instruction);
+            },
             // Conversion
             | Instruction::F2I
                 | Instruction::F2U
@@ -356,7 +991,7 @@ impl Vulkyn {
                 | Instruction::RB2U(_)
                 | Instruction::RB2C(_)
                 => {
-                    return self.conversion_operation(instruction);
+                    return self.conversion_operation(instruction, None);
             }
             Instruction::PUSH(either) => {
                 self.memory.push(self.get_either(either));
@@ -367,6 +1002,32 @@ impl Vulkyn {
                     return State::StackUnderflow
                 };
             },
+            Instruction::DUP
+                | Instruction::SWAP
+                | Instruction::OVER
+                | Instruction::ROT
+                | Instruction::PICK(_)
+                | Instruction::ROLL(_)
+                | Instruction::DROPN(_)
+                => {
+                return self.stack_operation(instruction);
+            },
+            Instruction::VNEW
+                | Instruction::VPUSH
+                | Instruction::VPOP
+                | Instruction::VGET
+                | Instruction::VSET
+                | Instruction::VLEN
+                | Instruction::MNEW
+                | Instruction::MSET
+                | Instruction::MGET
+                | Instruction::MHAS
+                | Instruction::MDEL
+                | Instruction::MLEN
+                | Instruction::OFREE
+                => {
+                return self.object_operation(instruction);
+            },
             Instruction::SCOPY(reg) => {
                 let some_word = self.memory.peek();
                 if some_word.is_err() {
@@ -397,32 +1058,36 @@ impl Vulkyn {
                 self.memory.registers.set(reg, word);
                 return State::OK;
             },
-            Instruction::LOAD((reg,offset)) => {
-                let idx = self.memory.registers.get(reg) + Word::I64(offset);
+            Instruction::LOAD(addr) => {
+                let idx = self.stack_address(addr);
                 let Ok(word) = self.memory.stack_read(idx) else {
                     return State::SegmentationFault;
                 };
                 self.memory.push(word);
                 return State::OK;
             },
-            Instruction::LOADB((reg,offset),size ) => {
-                let idx = self.memory.registers.get(reg) + Word::I64(offset);
+            Instruction::LOADB(addr,size ) => {
+                let idx = self.stack_address(addr);
                 let Ok(words) = self.memory.stack_read_range(idx,size) else {
                     return State::SegmentationFault;
                 };
                 self.memory.extend(words);
                 return State::OK;
             }
-            Instruction::READU((addr_reg,addr_offset), size, offset ) => {
-                let addr = self.memory.registers.get(addr_reg) + Word::I64(addr_offset);
+            Instruction::READU(addr, size, offset ) => {
+                let Some(addr) = self.checked_address(addr, offset + size) else {
+                    return State::SegmentationFault;
+                };
                 let Ok(words) = self.memory.read(addr, size, offset) else {
                     return State::SegmentationFault;
                 };
                 self.memory.extend(words);
                 return State::OK;
             },
-            Instruction::READD((addr_reg,addr_offset), size, offset ) => {
-                let addr = self.memory.registers.get(addr_reg) + Word::I64(addr_offset);
+            Instruction::READD(addr, size, offset ) => {
+                let Some(addr) = self.checked_address(addr, offset + size) else {
+                    return State::SegmentationFault;
+                };
                 let Ok(mut words) = self.memory.read(addr, size, offset) else {
                     return State::SegmentationFault;
                 };
@@ -434,6 +1099,9 @@ impl Vulkyn {
                 let Ok(addr) = self.memory.pop() else {
                     return State::StackUnderflow
                 };
+                if !self.checked_access(addr, addr, offset + size) {
+                    return State::SegmentationFault;
+                }
                 let Ok(words) = self.memory.read(addr, size, offset) else {
                     return State::SegmentationFault;
                 };
@@ -445,6 +1113,9 @@ impl Vulkyn {
                 let Ok(addr) = self.memory.pop() else {
                     return State::StackUnderflow
                 };
+                if !self.checked_access(addr, addr, offset + size) {
+                    return State::SegmentationFault;
+                }
                 let Ok(mut words) = self.memory.read(addr, size, offset) else {
                     return State::SegmentationFault;
                 };
@@ -452,9 +1123,20 @@ impl Vulkyn {
                 self.memory.extend(words);
                 return State::OK;
             },
-            Instruction::WRITE(word,(reg,offset) ) => {
-                let addr = self.memory.registers.get(reg);
-                let Ok(_) = self.memory.write(word,addr,offset) else {
+            Instruction::WRITE(value,addr ) => {
+                let word = match value {
+                    Some(e) => self.get_either(e),
+                    None => {
+                        let Ok(word) = self.memory.pop() else {
+                            return State::StackUnderflow
+                        };
+                        word
+                    }
+                };
+                let Some(addr) = self.checked_address(addr, 1) else {
+                    return State::SegmentationFault;
+                };
+                let Ok(_) = self.memory.write(word,addr,0) else {
                     return State::SegmentationFault;
                 };
                 return State::OK;
@@ -466,6 +1148,9 @@ impl Vulkyn {
                 let Ok(word) = self.memory.pop() else {
                     return State::StackUnderflow
                 };                 
+                if !self.checked_access(addr, addr, 1) {
+                    return State::SegmentationFault;
+                }
                 let Ok(_) = self.memory.write(word,addr,0) else {
                     return State::SegmentationFault;
                 };
@@ -478,8 +1163,11 @@ impl Vulkyn {
                 self.memory.push(addr);
                 return State::OK;
             },
-            Instruction::FREE((addr_reg,addr_offset) ) => {
-                let addr = self.memory.registers.get(addr_reg) + Word::I64(addr_offset);
+            Instruction::FREE(addr) => {
+                let (base,addr) = (self.memory.registers.get(addr.base),self.heap_address(addr));
+                if !self.checked_free(base, addr) {
+                    return State::SegmentationFault;
+                }
                 let Ok(_) = self.memory.free(addr) else {
                     return State::SegmentationFault;
                 };
@@ -489,11 +1177,89 @@ impl Vulkyn {
                 let Ok(addr) = self.memory.pop() else {
                     return State::StackUnderflow
                 };
+                if !self.checked_free(addr, addr) {
+                    return State::SegmentationFault;
+                }
                 let Ok(_) = self.memory.free(addr) else {
                     return State::SegmentationFault;
                 };
                 return State::OK;
             },
+            Instruction::COPY(dst,src,count) => {
+                let count = self.get_either(count).as_usize();
+                let Some(dst) = self.checked_address(dst, count) else {
+                    return State::SegmentationFault;
+                };
+                let Some(src) = self.checked_address(src, count) else {
+                    return State::SegmentationFault;
+                };
+                let Ok(_) = self.memory.copy(dst,src,count) else {
+                    return State::SegmentationFault;
+                };
+                return State::OK;
+            },
+            Instruction::FILL(dst,value,count) => {
+                let word = self.get_either(value);
+                let count = self.get_either(count).as_usize();
+                let Some(dst) = self.checked_address(dst, count) else {
+                    return State::SegmentationFault;
+                };
+                let Ok(_) = self.memory.fill(dst,word,count) else {
+                    return State::SegmentationFault;
+                };
+                return State::OK;
+            },
+            Instruction::COMPARE(lhs,rhs,count) => {
+                let count = self.get_either(count).as_usize();
+                let Some(lhs) = self.checked_address(lhs, count) else {
+                    return State::SegmentationFault;
+                };
+                let Some(rhs) = self.checked_address(rhs, count) else {
+                    return State::SegmentationFault;
+                };
+                let Ok(word) = self.memory.compare(lhs,rhs,count) else {
+                    return State::SegmentationFault;
+                };
+                self.memory.push(word);
+                return State::OK;
+            },
+            Instruction::LOAD8(addr,signed) => {
+                return self.linear_load(addr,1,signed);
+            },
+            Instruction::LOAD16(addr,signed) => {
+                return self.linear_load(addr,2,signed);
+            },
+            Instruction::LOAD32(addr,signed) => {
+                return self.linear_load(addr,4,signed);
+            },
+            Instruction::LOAD64(addr,signed) => {
+                return self.linear_load(addr,8,signed);
+            },
+            Instruction::STORE8(value,addr) => {
+                return self.linear_store(value,addr,1);
+            },
+            Instruction::STORE16(value,addr) => {
+                return self.linear_store(value,addr,2);
+            },
+            Instruction::STORE32(value,addr) => {
+                return self.linear_store(value,addr,4);
+            },
+            Instruction::STORE64(value,addr) => {
+                return self.linear_store(value,addr,8);
+            },
+            Instruction::MEMSIZE => {
+                self.memory.push(Word::U64(self.memory.mem_size()));
+                return State::OK;
+            },
+            Instruction::MEMGROW(size) => {
+                let previous = self.memory.mem_grow(size);
+                self.memory.push(Word::U64(previous));
+                return State::OK;
+            },
+            Instruction::ENDIAN(endian) => {
+                self.memory.endian = endian;
+                return State::OK;
+            },
             Instruction::DMP => {
                 let Ok(word) = self.memory.pop() else {
                     return State::StackUnderflow
@@ -513,6 +1279,22 @@ impl Vulkyn {
             Instruction::GO(label)=> {
                 self.memory.registers.Ni = Word::U64(label);
             },
+            Instruction::RGO(reg)=> {
+                self.memory.registers.Ni = self.memory.registers.get(reg);
+            },
+            Instruction::SGO=> {
+                let Ok(word) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                self.memory.registers.Ni = word;
+            },
+            Instruction::SWITCH(table,default) => {
+                let Ok(word) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                let addr = table.get(word.as_usize()).unwrap_or(&default);
+                self.memory.registers.Ni = Word::U64(*addr);
+            },
             Instruction::GOIF(label) => {
                 let Ok(word) = self.memory.pop() else {
                     return State::StackUnderflow
@@ -527,6 +1309,58 @@ impl Vulkyn {
                     self.memory.registers.Ni = Word::U64(label);
                 }
             },
+            Instruction::GOEQ(_,_,_)
+                | Instruction::GONE(_,_,_)
+                | Instruction::GOLT(_,_,_)
+                | Instruction::GOLE(_,_,_)
+                | Instruction::GOGT(_,_,_)
+                | Instruction::GOGE(_,_,_)
+                | Instruction::GOZ(_,_)
+                | Instruction::GONZ(_,_)
+                | Instruction::LOOP(_,_)
+                => {
+                return self.branch_operation(instruction);
+            },
+            Instruction::GOFZ(label) => {
+                if self.condition(COND_ZERO) {
+                    self.memory.registers.Ni = Word::U64(label);
+                }
+            },
+            Instruction::GOFNZ(label) => {
+                if !self.condition(COND_ZERO) {
+                    self.memory.registers.Ni = Word::U64(label);
+                }
+            },
+            Instruction::GOFN(label) => {
+                if self.condition(COND_NEGATIVE) {
+                    self.memory.registers.Ni = Word::U64(label);
+                }
+            },
+            Instruction::GOFNN(label) => {
+                if !self.condition(COND_NEGATIVE) {
+                    self.memory.registers.Ni = Word::U64(label);
+                }
+            },
+            Instruction::GOFC(label) => {
+                if self.condition(COND_CARRY) {
+                    self.memory.registers.Ni = Word::U64(label);
+                }
+            },
+            Instruction::GOFNC(label) => {
+                if !self.condition(COND_CARRY) {
+                    self.memory.registers.Ni = Word::U64(label);
+                }
+            },
+            Instruction::GOFV(label) => {
+                if self.condition(COND_OVERFLOW) {
+                    self.memory.registers.Ni = Word::U64(label);
+                }
+            },
+            Instruction::GOFNV(label) => {
+                if !self.condition(COND_OVERFLOW) {
+                    self.memory.registers.Ni = Word::U64(label);
+                }
+            },
         }
         return State::OK;
     }
@@ -538,62 +1372,283 @@ impl Vulkyn {
         let Ok(y) = self.memory.pop() else {
             return State::StackUnderflow
         };
-        match instruction {
-            Instruction::ADD => {
-                let result = x  + y ;
-                self.memory.push(result);
-                return State::OK
-            }
-            Instruction::MINUS => {
-                let result = x  - y ;
-                self.memory.push(result);
-                return State::OK
-            }
-            Instruction::MUL => {
-                let result = x  * y ;
-                self.memory.push(result);
-                return State::OK
-            }
-            Instruction::MOD => {
-                let result = x  % y ;
-                self.memory.push(result);
-                return State::OK
-            }
-            Instruction::DIV => {
+        let result = match self.arithmetic(instruction, x, y) {
+            Ok(result) => result,
+            Err(state) => return state
+        };
+        self.memory.push(result);
+        return State::OK
+    }
+    // Compute x op y and update the condition flags of He,
+    // fails on a division by zero or, in checked mode, on a pointer
+    // leaving its allocation
+    fn arithmetic(&mut self,instruction : Instruction,x : Word,y : Word) -> Result<Word,State>{
+        let (result,carry,overflow) = match instruction {
+            Instruction::ADD | Instruction::RADD(_,_) => x.overflowing_add(y),
+            Instruction::MINUS | Instruction::RMINUS(_,_) => x.overflowing_sub(y),
+            Instruction::MUL | Instruction::RMUL(_,_) => x.overflowing_mul(y),
+            Instruction::MOD | Instruction::RMOD(_,_) => (x % y,false,false),
+            Instruction::DIV | Instruction::RDIV(_,_) => {
                 if y.is_zero() {
-                    return State::DivisionZero;
+                    return Err(State::DivisionZero);
                 }
-                let result = x / y;
-                self.memory.push(result);
-                return State::OK
+                (x / y,false,false)
+            }
+            _ => return Ok(Word::init())
+        };
+        if let Word::PTR(_) = result {
+            let source = if let Word::PTR(_) = x { x } else { y };
+            if self.checked && self.memory.allocation(source) != self.memory.allocation(result) {
+                return Err(State::SegmentationFault);
             }
-            _ => {}
         }
-        return State::OK
+        self.set_condition(result, carry, overflow);
+        return Ok(result);
     }
-    fn get_either(&self,e: Either<Word, Register>) -> Word{
-        match e {
-            Either::Left(word) => word,
-            Either::Right(reg) => self.memory.registers.get(reg)
+    fn set_condition(&mut self,result : Word,carry : bool,overflow : bool){
+        let mut flags = Word::U64(0);
+        if result.is_zero() {
+            flags = flags | COND_ZERO;
+        }
+        if result.is_negative() {
+            flags = flags | COND_NEGATIVE;
+        }
+        if carry {
+            flags = flags | COND_CARRY;
+        }
+        if overflow {
+            flags = flags | COND_OVERFLOW;
         }
+        self.memory.registers.He = flags;
     }
-    fn bitewise_operation(&mut self,instruction : Instruction) -> State{
-        let Ok(x) = self.memory.pop() else {
-            return State::StackUnderflow
+    // Comparison set zero when x == y and negative when x < y
+    fn compare_condition(&mut self,x : Word,y : Word){
+        let mut flags = Word::U64(0);
+        if x == y {
+            flags = flags | COND_ZERO;
+        }
+        if x < y {
+            flags = flags | COND_NEGATIVE;
+        }
+        self.memory.registers.He = flags;
+    }
+    fn condition(&self,cond : Word) -> bool{
+        (self.memory.registers.He & cond) == cond
+    }
+    fn stack_operation(&mut self,instruction : Instruction) -> State{
+        let res = match instruction {
+            Instruction::DUP => self.memory.pick(0).map(|_| ()),
+            Instruction::OVER => self.memory.pick(1).map(|_| ()),
+            Instruction::PICK(n) => self.memory.pick(n).map(|_| ()),
+            Instruction::SWAP => self.memory.roll(1).map(|_| ()),
+            Instruction::ROT => self.memory.roll(2).map(|_| ()),
+            Instruction::ROLL(n) => self.memory.roll(n).map(|_| ()),
+            Instruction::DROPN(n) => self.memory.drop(n),
+            _ => Ok(())
         };
-        let Ok(y) = self.memory.pop() else {
-            return State::StackUnderflow
+        if res.is_err() {
+            return State::StackUnderflow;
+        }
+        return State::OK;
+    }
+    fn object_operation(&mut self,instruction : Instruction) -> State{
+        let arity = match instruction {
+            Instruction::VNEW | Instruction::MNEW => 0,
+            Instruction::VPOP | Instruction::VLEN | Instruction::MLEN | Instruction::OFREE => 1,
+            Instruction::VSET | Instruction::MSET => 3,
+            _ => 2
         };
-        match instruction {
-            Instruction::BAND  => {
-                let result = x & y ;
-                self.memory.push(result);
-                return State::OK
+        let mut operands = [Word::init();3];
+        for i in (0..arity).rev() {
+            let Ok(word) = self.memory.pop() else {
+                return State::StackUnderflow
+            };
+            operands[i] = word;
+        }
+        let [handle,x,y] = operands;
+        let res = match instruction {
+            Instruction::VNEW => Ok(Some(self.memory.new_object(Object::Vector(Vec::new())))),
+            Instruction::VPUSH => self.memory.vector_push(handle, x).map(|_| None),
+            Instruction::VPOP => self.memory.vector_pop(handle).map(Some),
+            Instruction::VGET => self.memory.vector_get(handle, x).map(Some),
+            Instruction::VSET => self.memory.vector_set(handle, x, y).map(|_| None),
+            Instruction::VLEN => self.memory.vector_len(handle).map(|len| Some(Word::U64(len))),
+            Instruction::MNEW => Ok(Some(self.memory.new_object(Object::Map(HashMap::new())))),
+            Instruction::MSET => self.memory.map_insert(handle, x, y).map(|_| None),
+            Instruction::MGET => self.memory.map_get(handle, x).map(Some),
+            Instruction::MHAS => self.memory.map_contains(handle, x).map(|has| Some(Word::BOOL(has))),
+            Instruction::MDEL => self.memory.map_remove(handle, x).map(|_| None),
+            Instruction::MLEN => self.memory.map_len(handle).map(|len| Some(Word::U64(len))),
+            Instruction::OFREE => self.memory.free_object(handle).map(|_| None),
+            _ => Ok(None)
+        };
+        match res {
+            Ok(Some(word)) => self.memory.push(word),
+            Ok(None) => {}
+            Err(_) => return State::SegmentationFault
+        }
+        return State::OK;
+    }
+    fn branch_operation(&mut self,instruction : Instruction) -> State{
+        let (jump,label) = match instruction {
+            Instruction::GOEQ(e1,e2,label) => {
+                let x = self.get_either(e1);
+                let y = self.get_either(e2);
+                self.compare_condition(x, y);
+                (x == y,label)
             }
-            Instruction::BOR  => {
-                let result = x | y ;
-                self.memory.push(result);
-                return State::OK
+            Instruction::GONE(e1,e2,label) => {
+                let x = self.get_either(e1);
+                let y = self.get_either(e2);
+                self.compare_condition(x, y);
+                (x != y,label)
+            }
+            Instruction::GOLT(e1,e2,label) => {
+                let x = self.get_either(e1);
+                let y = self.get_either(e2);
+                self.compare_condition(x, y);
+                (x < y,label)
+            }
+            Instruction::GOLE(e1,e2,label) => {
+                let x = self.get_either(e1);
+                let y = self.get_either(e2);
+                self.compare_condition(x, y);
+                (x <= y,label)
+            }
+            Instruction::GOGT(e1,e2,label) => {
+                let x = self.get_either(e1);
+                let y = self.get_either(e2);
+                self.compare_condition(x, y);
+                (x > y,label)
+            }
+            Instruction::GOGE(e1,e2,label) => {
+                let x = self.get_either(e1);
+                let y = self.get_either(e2);
+                self.compare_condition(x, y);
+                (x >= y,label)
+            }
+            Instruction::GOZ(e,label) => (self.get_either(e).is_zero(),label),
+            Instruction::GONZ(e,label) => (!self.get_either(e).is_zero(),label),
+            Instruction::LOOP(reg,label) => {
+                let (word,_,_) = self.memory.registers.get(reg).overflowing_sub(Word::U64(1));
+                self.memory.registers.set(reg, word);
+                (!word.is_zero(),label)
+            }
+            _ => return State::IllegalInstruction
+        };
+        if jump {
+            self.memory.registers.Ni = Word::U64(label);
+        }
+        return State::OK
+    }
+    // Result of an R-form : into the destination register if any, else on the stack
+    fn output(&mut self,dest : Option<Register>,word : Word){
+        match dest {
+            Some(reg) => self.memory.registers.set(reg, word),
+            None => self.memory.push(word),
+        }
+    }
+    // index*scale + displacement of an address operand
+    fn displacement(&self,addr : Address) -> isize{
+        let index = match addr.index {
+            Some((reg,scale)) => (self.memory.registers.get(reg).as_usize() as isize).wrapping_mul(scale as isize),
+            None => 0
+        };
+        index.wrapping_add(addr.displacement)
+    }
+    fn stack_address(&self,addr : Address) -> Word{
+        let base = self.memory.registers.get(addr.base).as_usize() as isize;
+        Word::U64(base.wrapping_add(self.displacement(addr)) as usize)
+    }
+    // heap words are 16 bytes apart, a pointer base gives a pointer
+    fn heap_address(&self,addr : Address) -> Word{
+        let base = self.memory.registers.get(addr.base);
+        let idx = (base.as_usize() as isize).wrapping_add(self.displacement(addr).wrapping_mul(16)) as usize;
+        if let Word::PTR(_) = base {
+            return Word::PTR(idx);
+        }
+        Word::U64(idx)
+    }
+    // In checked mode, addr must be a pointer and the count words from it
+    // must stay within the allocation of base
+    fn checked_access(&self,base : Word,addr : Word,count : usize) -> bool{
+        if !self.checked || self.memory.device_range(addr, count) {
+            return true;
+        }
+        let (Word::PTR(_),Word::PTR(idx)) = (base,addr) else {
+            return false;
+        };
+        let Some((start,size)) = self.memory.allocation(base) else {
+            return false;
+        };
+        return idx >= start && idx + count*16 <= start + size*16;
+    }
+    fn checked_address(&self,addr : Address,count : usize) -> Option<Word>{
+        let base = self.memory.registers.get(addr.base);
+        let addr = self.heap_address(addr);
+        if !self.checked_access(base, addr, count) {
+            return None;
+        }
+        return Some(addr);
+    }
+    // In checked mode only the base of an allocation can be freed
+    fn checked_free(&self,base : Word,addr : Word) -> bool{
+        if !self.checked_access(base, addr, 0) {
+            return false;
+        }
+        return !self.checked || self.memory.allocation(addr).map(|(start,_)| start) == Some(addr.as_usize());
+    }
+    fn linear_address(&self,addr : Address) -> usize{
+        let base = self.memory.registers.get(addr.base).as_usize() as isize;
+        base.wrapping_add(self.displacement(addr)) as usize
+    }
+    fn linear_load(&mut self,addr : Address,width : usize,signed : bool) -> State{
+        let addr = self.linear_address(addr);
+        let Ok(word) = self.memory.load_bytes(addr,width,signed) else {
+            return State::SegmentationFault;
+        };
+        self.memory.push(word);
+        return State::OK;
+    }
+    fn linear_store(&mut self,value : Option<Either<Word,Register>>,addr : Address,width : usize) -> State{
+        let word = match value {
+            Some(e) => self.get_either(e),
+            None => {
+                let Ok(word) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                word
+            }
+        };
+        let addr = self.linear_address(addr);
+        let Ok(_) = self.memory.store_bytes(addr,width,word) else {
+            return State::SegmentationFault;
+        };
+        return State::OK;
+    }
+    fn get_either(&self,e: Either<Word, Register>) -> Word{
+        match e {
+            Either::Left(word) => word,
+            Either::Right(reg) => self.memory.registers.get(reg)
+        }
+    }
+    fn bitewise_operation(&mut self,instruction : Instruction) -> State{
+        let Ok(x) = self.memory.pop() else {
+            return State::StackUnderflow
+        };
+        let Ok(y) = self.memory.pop() else {
+            return State::StackUnderflow
+        };
+        match instruction {
+            Instruction::BAND  => {
+                let result = x & y ;
+                self.memory.push(result);
+                return State::OK
+            }
+            Instruction::BOR  => {
+                let result = x | y ;
+                self.memory.push(result);
+                return State::OK
             }
             Instruction::BXOR  => {
                 let result = x ^ y ;
@@ -614,41 +1669,41 @@ impl Vulkyn {
         }
         return State::OK;
     }
-    fn r_bitewise_operation(&mut self,instruction : Instruction) -> State{
+    fn r_bitewise_operation(&mut self,instruction : Instruction,dest : Option<Register>) -> State{
         match instruction {
             Instruction::RBAND(e1,e2)  => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
                 let result = x & y ;
-                self.memory.push(result);
+                self.output(dest, result);
                 return State::OK
             }
             Instruction::RBOR(e1,e2)  => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
                 let result = x | y ;
-                self.memory.push(result);
+                self.output(dest, result);
                 return State::OK
             }
             Instruction::RBXOR(e1,e2)  => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
                 let result = x ^ y ;
-                self.memory.push(result);
+                self.output(dest, result);
                 return State::OK
             }
             Instruction::RLSHIFT(e1,e2)  => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
                 let result = x << y ;
-                self.memory.push(result);
+                self.output(dest, result);
                 return State::OK
             }
             Instruction::RRSHIFT(e1,e2) => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
                 let result = x >> y ;
-                self.memory.push(result);
+                self.output(dest, result);
                 return State::OK
             }
             _ => {}
@@ -681,31 +1736,37 @@ impl Vulkyn {
                 return State::OK;
             }
             Instruction::LESS  => {
+                self.compare_condition(x, y);
                 let result = x < y;
                 self.memory.push(Word::BOOL(result));
                 return State::OK;
             }
             Instruction::GREAT => {
+                self.compare_condition(x, y);
                 let result = x > y;
                 self.memory.push(Word::BOOL(result));
                 return State::OK;
             }
             Instruction::ELESS  => {
+                self.compare_condition(x, y);
                 let result = x <= y;
                 self.memory.push(Word::BOOL(result));
                 return State::OK;
             }
             Instruction::EGREAT => {
+                self.compare_condition(x, y);
                 let result = x >= y;
                 self.memory.push(Word::BOOL(result));
                 return State::OK;
             }
             Instruction::EQUAL => {
+                self.compare_condition(x, y);
                 let result = x == y;
                 self.memory.push(Word::BOOL(result));
                 return State::OK;
             }
             Instruction::DIFF => {
+                self.compare_condition(x, y);
                 let result = x != y;
                 self.memory.push(Word::BOOL(result));
                 return State::OK;
@@ -714,188 +1775,772 @@ impl Vulkyn {
         }
         return State::OK;
     }
-    fn r_boolean_operation(&mut self,instruction : Instruction) -> State{
+    fn r_boolean_operation(&mut self,instruction : Instruction,dest : Option<Register>) -> State{
         match instruction {
             Instruction::RAND(e1,e2) => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
                 let result = x.and(&y);
-                self.memory.push(result);
+                self.output(dest, result);
                 return State::OK;
             }
             | Instruction::ROR(e1,e2)  => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
                 let result = x.or(&y);
-                self.memory.push(result);
+                self.output(dest, result);
                 return State::OK;
             }
             | Instruction::RLESS(e1,e2)  => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
+                self.compare_condition(x, y);
                 let result = x < y;
-                self.memory.push(Word::BOOL(result));
+                self.output(dest, Word::BOOL(result));
                 return State::OK;
             }
             | Instruction::RGREAT(e1,e2) => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
+                self.compare_condition(x, y);
                 let result = x > y;
-                self.memory.push(Word::BOOL(result));
+                self.output(dest, Word::BOOL(result));
                 return State::OK;
             }
             | Instruction::RELESS(e1,e2)  => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
+                self.compare_condition(x, y);
                 let result = x <= y;
-                self.memory.push(Word::BOOL(result));
+                self.output(dest, Word::BOOL(result));
                 return State::OK;
             }
             | Instruction::REGREAT(e1,e2) => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
+                self.compare_condition(x, y);
                 let result = x >= y;
-                self.memory.push(Word::BOOL(result));
+                self.output(dest, Word::BOOL(result));
                 return State::OK;
             }
             | Instruction::REQUAL(e1,e2) => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
+                self.compare_condition(x, y);
                 let result = x == y;
-                self.memory.push(Word::BOOL(result));
+                self.output(dest, Word::BOOL(result));
                 return State::OK;
             }
             | Instruction::RDIFF(e1,e2) => {
                 let x = self.get_either(e1);
                 let y = self.get_either(e2);
+                self.compare_condition(x, y);
                 let result = x != y;
-                self.memory.push(Word::BOOL(result));
+                self.output(dest, Word::BOOL(result));
                 return State::OK;
             }
             | Instruction::RNOT(e) => {
                 let x = self.get_either(e);
-                self.memory.push(x.neg());
+                self.output(dest, x.neg());
                 return State::OK;
             }
             _ => {}
         }
         return State::OK;
     }
-    fn r_operation(&mut self,instruction : Instruction) -> State{
-        match instruction {
-            Instruction::RADD(e1,e2) => {
-                let x = self.get_either(e1);
-                let y = self.get_either(e2);
-                let result = x + y;
-                self.memory.push(result);
-                return State::OK;
-            }
-            Instruction::RMINUS(e1,e2) => {
-                let x = self.get_either(e1);
-                let y = self.get_either(e2);
-                let result = x - y;
-                self.memory.push(result);
-                return State::OK;
-            }
-            Instruction::RMUL(e1,e2) => {
-                let x = self.get_either(e1);
-                let y = self.get_either(e2);
-                let result = x * y;
-                self.memory.push(result);
-                return State::OK;
-            }
-            Instruction::RMOD(e1,e2) => {
-                let x = self.get_either(e1);
-                let y = self.get_either(e2);
-                let result = x % y;
-                self.memory.push(result);
-                return State::OK;
+    fn r_operation(&mut self,instruction : Instruction,dest : Option<Register>) -> State{
+        let (x,y) = match instruction.clone() {
+            Instruction::RADD(e1,e2)
+                | Instruction::RMINUS(e1,e2)
+                | Instruction::RMUL(e1,e2)
+                | Instruction::RMOD(e1,e2)
+                | Instruction::RDIV(e1,e2) => (self.get_either(e1),self.get_either(e2)),
+            _ => return State::OK
+        };
+        let result = match self.arithmetic(instruction, x, y) {
+            Ok(result) => result,
+            Err(state) => return state
+        };
+        self.output(dest, result);
+        return State::OK
+    }
+    fn float_operation(&mut self,instruction : Instruction) -> State{
+        let Ok(x) = self.memory.pop() else {
+            return State::StackUnderflow
+        };
+        let result = match instruction {
+            Instruction::SQRT => x.sqrt(),
+            Instruction::EXP => x.exp(),
+            Instruction::LN => x.ln(),
+            Instruction::SIN => x.sin(),
+            Instruction::COS => x.cos(),
+            Instruction::TAN => x.tan(),
+            Instruction::FLOOR => x.floor(),
+            Instruction::CEIL => x.ceil(),
+            Instruction::ROUND => x.round(),
+            Instruction::ABS => x.abs(),
+            Instruction::ISNAN => x.is_nan(),
+            Instruction::ISINF => x.is_inf(),
+            _ => {
+                let Ok(y) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                match instruction {
+                    Instruction::POW => x.pow(y),
+                    Instruction::ATAN2 => x.atan2(y),
+                    Instruction::MIN => x.min(y),
+                    Instruction::MAX => x.max(y),
+                    _ => return State::IllegalInstruction
+                }
             }
-            Instruction::RDIV(e1,e2) => {
-                let x = self.get_either(e1);
-                let y = self.get_either(e2);
-                if y.is_zero() {
-                    return State::DivisionZero;
+        };
+        self.memory.push(result);
+        return State::OK
+    }
+    fn integer_operation(&mut self,instruction : Instruction) -> State{
+        let Ok(x) = self.memory.pop() else {
+            return State::StackUnderflow
+        };
+        let result = match instruction {
+            Instruction::BNOT => x.bnot(),
+            Instruction::POPCNT => x.popcnt(),
+            Instruction::CLZ => x.clz(),
+            Instruction::CTZ => x.ctz(),
+            Instruction::NEG => x.negate(),
+            _ => {
+                let Ok(y) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                match instruction {
+                    Instruction::ASHR => x.ashr(y),
+                    Instruction::ROTL => x.rotl(y),
+                    Instruction::ROTR => x.rotr(y),
+                    _ => return State::IllegalInstruction
                 }
-                let result = x / y;
-                self.memory.push(result);
-                return State::OK;
             }
-            _ => {}
-        }
+        };
+        self.memory.push(result);
+        return State::OK
+    }
+    fn r_integer_operation(&mut self,instruction : Instruction,dest : Option<Register>) -> State{
+        let result = match instruction {
+            Instruction::RBNOT(e) => self.get_either(e).bnot(),
+            Instruction::RPOPCNT(e) => self.get_either(e).popcnt(),
+            Instruction::RCLZ(e) => self.get_either(e).clz(),
+            Instruction::RCTZ(e) => self.get_either(e).ctz(),
+            Instruction::RNEG(e) => self.get_either(e).negate(),
+            Instruction::RASHR(e1,e2) => self.get_either(e1).ashr(self.get_either(e2)),
+            Instruction::RROTL(e1,e2) => self.get_either(e1).rotl(self.get_either(e2)),
+            Instruction::RROTR(e1,e2) => self.get_either(e1).rotr(self.get_either(e2)),
+            _ => return State::IllegalInstruction
+        };
+        self.output(dest, result);
         return State::OK
     }
+    fn r_float_operation(&mut self,instruction : Instruction,dest : Option<Register>) -> State{
+        let result = match instruction {
+            Instruction::RSQRT(e) => self.get_either(e).sqrt(),
+            Instruction::REXP(e) => self.get_either(e).exp(),
+            Instruction::RLN(e) => self.get_either(e).ln(),
+            Instruction::RSIN(e) => self.get_either(e).sin(),
+            Instruction::RCOS(e) => self.get_either(e).cos(),
+            Instruction::RTAN(e) => self.get_either(e).tan(),
+            Instruction::RFLOOR(e) => self.get_either(e).floor(),
+            Instruction::RCEIL(e) => self.get_either(e).ceil(),
+            Instruction::RROUND(e) => self.get_either(e).round(),
+            Instruction::RABS(e) => self.get_either(e).abs(),
+            Instruction::RISNAN(e) => self.get_either(e).is_nan(),
+            Instruction::RISINF(e) => self.get_either(e).is_inf(),
+            Instruction::RPOW(e1,e2) => self.get_either(e1).pow(self.get_either(e2)),
+            Instruction::RATAN2(e1,e2) => self.get_either(e1).atan2(self.get_either(e2)),
+            Instruction::RMIN(e1,e2) => self.get_either(e1).min(self.get_either(e2)),
+            Instruction::RMAX(e1,e2) => self.get_either(e1).max(self.get_either(e2)),
+            _ => return State::IllegalInstruction
+        };
+        self.output(dest, result);
+        return State::OK
+    }
+    // Calling convention : the caller pushes the arguments, the call then
+    // inserts the caller's Li and the return address below them
+    //   [saved Li][return address][argument 0]...[argument n-1]
+    // and Li points to the return address, argument k is at [li | k+1].
+    // RET n keeps the n words on top of the stack as return values,
+    // drops the rest of the frame and restores Ni and Li.
     fn function_operation(&mut self,instruction : Instruction) -> State{
         match instruction {
             Instruction::CALL(label) => {
-                self.memory.push(self.memory.registers.Ni);
-                self.memory.registers.Li = self.memory.registers.Ts;
-                self.memory.registers.Ni = Word::U64(label);
+                return self.call(Word::U64(label), 0);
             },
-
-            | Instruction::CALLP(label, size) => {
-                let Ok(addr) = self.memory.insert(
-                    self.memory.registers.Ni,size) 
-                else {
-                    return State::StackUnderflow
-                };
-                self.memory.registers.Li = addr;
-                self.memory.registers.Ni = Word::U64(label);
+            Instruction::CALLP(label, size) => {
+                return self.call(Word::U64(label), size);
             },
-            | Instruction::SCALL => {
-                self.memory.push(self.memory.registers.Ni);
-                self.memory.registers.Li = self.memory.registers.Ts;
+            Instruction::SCALL => {
                 let Ok(word) = self.memory.pop() else {
                     return State::StackUnderflow
                 };
-                self.memory.registers.Ni = word;
+                return self.call(word, 0);
             },
-            | Instruction::SCALLP(size) => {
-                let Ok(addr) = self.memory.insert(
-                    self.memory.registers.Ni,size) 
-                else {
-                    return State::StackUnderflow
-                };
+            Instruction::SCALLP(size) => {
                 let Ok(word) = self.memory.pop() else {
                     return State::StackUnderflow
                 };
-                self.memory.registers.Li = addr;
-                self.memory.registers.Ni = word;
+                return self.call(word, size);
             },
-            | Instruction::RCALL(reg) => {
-                let word = self.memory.registers.get(reg);
-                self.memory.registers.Li = self.memory.registers.Ts;
-                self.memory.push(self.memory.registers.Ni);
-                self.memory.registers.Ni = word;
+            Instruction::RCALL(reg) => {
+                return self.call(self.memory.registers.get(reg), 0);
             },
-            | Instruction::RCALLP(reg, size) => {
-                self.memory.push(self.memory.registers.Ni);
-                let word = self.memory.registers.get(reg);
-                let Ok(addr) = self.memory.insert(
-                    self.memory.registers.Ni,size) 
-                else {
+            Instruction::RCALLP(reg, size) => {
+                return self.call(self.memory.registers.get(reg), size);
+            },
+            Instruction::TAILCALL(label, size) => {
+                let start = self.memory.registers.Li.as_usize() + 1;
+                let Some(end) = self.memory.stack_size.checked_sub(size) else {
                     return State::StackUnderflow
                 };
-                self.memory.registers.Li = addr;
-                self.memory.registers.Ni = word;
+                if let Err(_) = self.memory.stack_clean(start,end){
+                    return State::SegmentationFault
+                }
+                self.memory.registers.Ni = Word::U64(label);
             },
             Instruction::RET(size) => {
-                let start = self.memory.registers.Li.as_usize();
-                let end = self.memory.stack_size - size;
-                let Ok(word) = self.memory.stack_read(self.memory.registers.Li) else {
+                let link = self.memory.registers.Li.as_usize();
+                if link == 0 && !self.resumed.is_empty() {
+                    return self.finish(size);
+                }
+                if link == 0 {
+                    return State::StackUnderflow;
+                }
+                let Ok(word) = self.memory.stack_read(Word::U64(link)) else {
+                    return State::StackOverflow;
+                };
+                let Ok(saved) = self.memory.stack_read(Word::U64(link - 1)) else {
                     return State::StackOverflow;
                 };
+                let Some(end) = self.memory.stack_size.checked_sub(size) else {
+                    return State::StackUnderflow
+                };
+                if let Err(_) = self.memory.stack_clean(link - 1,end){
+                    return State::SegmentationFault
+                }
                 self.memory.registers.Ni = word;
-                if let Err(_) = self.memory.stack_clean(start,end){
+                self.memory.registers.Li = saved;
+            }
+            Instruction::PUSHR(registers) => {
+                for reg in registers {
+                    self.memory.save(self.memory.registers.get(reg));
+                }
+            }
+            Instruction::POPR(registers) => {
+                for reg in registers.into_iter().rev() {
+                    let Ok(word) = self.memory.restore() else {
+                        return State::StackUnderflow
+                    };
+                    self.memory.registers.set(reg, word);
+                }
+            }
+            _ => {}
+        }
+        return State::OK
+    }
+    // Coroutines are only switched by RESUME, YIELD and the final RET,
+    // so a run is always scheduled the same way.
+    // RESUME pushes the yielded word, or the n return values of the
+    // final RET n of the coroutine.
+    fn coroutine_operation(&mut self,instruction : Instruction) -> State{
+        let operand = match &instruction {
+            Instruction::YIELD(value) | Instruction::RESUME(value) | Instruction::STATUS(value) => value.clone(),
+            _ => None,
+        };
+        let word = match (&instruction,operand) {
+            (Instruction::SPAWN(_,_),_) => Word::init(),
+            (_,Some(e)) => self.get_either(e),
+            (_,None) => {
+                let Ok(word) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                word
+            }
+        };
+        match instruction {
+            Instruction::SPAWN(label, size) => {
+                let mut args = Vec::with_capacity(size);
+                for _ in 0..size {
+                    let Ok(word) = self.memory.pop() else {
+                        return State::StackUnderflow
+                    };
+                    args.push(word);
+                }
+                args.reverse();
+                self.coroutines.push(Coroutine {
+                    context : Context::build(label, args),
+                    status : CoroutineStatus::Suspended,
+                });
+                self.memory.push(Word::U64(self.coroutines.len()));
+            }
+            Instruction::RESUME(_) => {
+                let idx = word.as_usize().wrapping_sub(1);
+                let Some(coroutine) = self.coroutines.get_mut(idx) else {
+                    return State::IllegalInstruction
+                };
+                if coroutine.status != CoroutineStatus::Suspended {
+                    return State::IllegalInstruction
+                }
+                coroutine.status = CoroutineStatus::Running;
+                self.memory.swap_context(&mut coroutine.context);
+                self.resumed.push(idx);
+            }
+            Instruction::YIELD(_) => {
+                let Some(idx) = self.resumed.pop() else {
+                    return State::IllegalInstruction
+                };
+                let coroutine = &mut self.coroutines[idx];
+                coroutine.status = CoroutineStatus::Suspended;
+                self.memory.swap_context(&mut coroutine.context);
+                self.memory.push(word);
+            }
+            Instruction::STATUS(_) => {
+                let Some(coroutine) = self.coroutines.get(word.as_usize().wrapping_sub(1)) else {
+                    return State::IllegalInstruction
+                };
+                self.memory.push(Word::U64(coroutine.status as usize));
+            }
+            _ => {}
+        }
+        return State::OK
+    }
+    // Final RET of the running coroutine
+    fn finish(&mut self,size : usize) -> State{
+        let mut values = Vec::with_capacity(size);
+        for _ in 0..size {
+            let Ok(word) = self.memory.pop() else {
+                return State::StackUnderflow
+            };
+            values.push(word);
+        }
+        let Some(idx) = self.resumed.pop() else {
+            return State::IllegalInstruction
+        };
+        let coroutine = &mut self.coroutines[idx];
+        coroutine.status = CoroutineStatus::Done;
+        self.memory.swap_context(&mut coroutine.context);
+        for word in values.into_iter().rev() {
+            self.memory.push(word);
+        }
+        return State::OK
+    }
+    fn time_operation(&mut self,instruction : Instruction) -> State{
+        let operand = match &instruction {
+            Instruction::SEED(operand) | Instruction::SLEEP(operand) => match operand {
+                Some(e) => Some(self.get_either(e.clone())),
+                None => {
+                    let Ok(word) = self.memory.pop() else {
+                        return State::StackUnderflow
+                    };
+                    Some(word)
+                }
+            },
+            Instruction::RANDOM(operand) => operand.clone().map(|e| self.get_either(e)),
+            _ => None,
+        };
+        match instruction {
+            Instruction::RANDOM(_) => {
+                let word = self.random.next_u64();
+                let bound = operand.map_or(0, |bound| bound.as_usize() as u64);
+                let word = if bound == 0 { word } else { ((word as u128 * bound as u128) >> 64) as u64 };
+                self.memory.push(Word::U64(word as usize));
+            }
+            Instruction::RANDF => {
+                let word = self.random.next_f64();
+                self.memory.push(Word::F64(word));
+            }
+            Instruction::SEED(_) => {
+                self.random.seed(operand.map_or(0, |seed| seed.to_bits()));
+            }
+            Instruction::CLOCK => {
+                self.memory.push(Word::U64(self.clock()));
+            }
+            Instruction::SLEEP(_) => {
+                let nanos = operand.map_or(0, |nanos| nanos.as_usize());
+                if self.wall_clock.is_some() {
+                    thread::sleep(Duration::from_nanos(nanos as u64));
+                }else {
+                    self.time = self.time.saturating_add(nanos);
+                }
+            }
+            _ => {}
+        }
+        return State::OK
+    }
+    // ARGV and GETENV push the address of a heap block of CHAR then its length,
+    // or 0 and -1 when the argument or the variable does not exist
+    fn args_operation(&mut self,instruction : Instruction) -> State{
+        let text = match instruction {
+            Instruction::ARGC => {
+                self.memory.push(Word::U64(self.args.len()));
+                return State::OK
+            }
+            Instruction::ARGV(index) => {
+                let index = match index {
+                    Some(e) => self.get_either(e),
+                    None => {
+                        let Ok(word) = self.memory.pop() else {
+                            return State::StackUnderflow
+                        };
+                        word
+                    }
+                };
+                self.args.get(index.as_usize()).cloned()
+            }
+            Instruction::GETENV => {
+                let (Ok(size),Ok(addr)) = (self.memory.pop(),self.memory.pop()) else {
+                    return State::StackUnderflow
+                };
+                let Some(name) = self.heap_string(addr, size.as_usize()) else {
+                    return State::SegmentationFault
+                };
+                self.env.get(&name).cloned()
+            }
+            _ => return State::OK
+        };
+        let Some(text) = text else {
+            self.memory.push(Word::U64(0));
+            self.memory.push(Word::I64(-1));
+            return State::OK
+        };
+        let chars : Vec<char> = text.chars().collect();
+        let Ok(addr) = self.memory.alloc(chars.len()) else {
+            return State::SegmentationFault
+        };
+        for (i,c) in chars.iter().enumerate() {
+            if self.memory.write(Word::CHAR(*c), addr, i as isize).is_err() {
+                return State::SegmentationFault
+            }
+        }
+        self.memory.push(addr);
+        self.memory.push(Word::I64(chars.len() as isize));
+        return State::OK
+    }
+    // Text of the count heap words from addr, CHAR words or bytes
+    fn heap_string(&mut self,addr : Word,count : usize) -> Option<String>{
+        if !self.checked_access(addr, addr, count) {
+            return None;
+        }
+        let words = self.memory.read(addr, count, 0).ok()?;
+        return Some(words.iter().map(|word| match word {
+            Word::CHAR(c) => *c,
+            word => word.as_usize() as u8 as char,
+        }).collect());
+    }
+    // Low byte of the count heap words from addr
+    fn heap_bytes(&mut self,addr : Word,count : usize) -> Option<Vec<u8>>{
+        if !self.checked_access(addr, addr, count) {
+            return None;
+        }
+        let words = self.memory.read(addr, count, 0).ok()?;
+        return Some(words.iter().map(|word| word.as_usize() as u8).collect());
+    }
+    fn store_heap_bytes(&mut self,addr : Word,bytes : &[u8]) -> bool{
+        for (i,byte) in bytes.iter().enumerate() {
+            if self.memory.write(Word::U64(*byte as usize), addr, i as isize).is_err() {
+                return false;
+            }
+        }
+        return true;
+    }
+    // Sockets move data one byte per heap word like the files, the result
+    // pushed is an I64 : a handle, a count or a port, or a negative error code
+    fn net_operation(&mut self,instruction : Instruction) -> State{
+        let arity = match instruction {
+            Instruction::SOCKSEND | Instruction::SOCKRECV => 3,
+            Instruction::LISTEN | Instruction::CONNECT => 2,
+            _ => 1
+        };
+        let mut operands = [Word::init();3];
+        for i in (0..arity).rev() {
+            let Ok(word) = self.memory.pop() else {
+                return State::StackUnderflow
+            };
+            operands[i] = word;
+        }
+        let [x,y,z] = operands;
+        let res = match instruction {
+            Instruction::LISTEN | Instruction::CONNECT => {
+                let (Ok(host),Ok(port)) = (u32::try_from(x.as_usize()),u16::try_from(y.as_usize())) else {
+                    self.memory.push(Word::I64(NET_INVALID));
+                    return State::OK
+                };
+                if let Instruction::LISTEN = instruction {
+                    self.net.listen(host, port)
+                }else {
+                    self.net.connect(host, port)
+                }
+            }
+            Instruction::ACCEPT => self.net.accept(x.as_usize()),
+            Instruction::SOCKSEND => {
+                let Some(bytes) = self.heap_bytes(y, z.as_usize()) else {
+                    return State::SegmentationFault
+                };
+                self.net.send(x.as_usize(), &bytes)
+            }
+            Instruction::SOCKRECV => {
+                if self.heap_bytes(y, z.as_usize()).is_none() {
+                    return State::SegmentationFault
+                }
+                match self.net.recv(x.as_usize(), z.as_usize()) {
+                    Ok(bytes) => {
+                        if !self.store_heap_bytes(y, &bytes) {
+                            return State::SegmentationFault
+                        }
+                        Ok(bytes.len())
+                    }
+                    Err(code) => Err(code)
+                }
+            }
+            Instruction::SOCKPORT => self.net.port(x.as_usize()),
+            Instruction::SOCKCLOSE => self.net.close(x.as_usize()).map(|_| 0),
+            _ => Ok(0)
+        };
+        match res {
+            Ok(value) => self.memory.push(Word::I64(value as isize)),
+            Err(code) => self.memory.push(Word::I64(code)),
+        }
+        return State::OK
+    }
+    // Files are moved one byte per heap word, the result pushed is an I64 :
+    // a handle, a count or a position, or a negative error code
+    fn file_operation(&mut self,instruction : Instruction) -> State{
+        let arity = match instruction {
+            Instruction::FREAD | Instruction::FWRITE => 3,
+            Instruction::FOPEN(_) | Instruction::FSEEK => 2,
+            _ => 1
+        };
+        let mut operands = [Word::init();3];
+        for i in (0..arity).rev() {
+            let Ok(word) = self.memory.pop() else {
+                return State::StackUnderflow
+            };
+            operands[i] = word;
+        }
+        let [x,y,z] = operands;
+        let res = match instruction {
+            Instruction::FOPEN(mode) => {
+                let Some(path) = self.heap_string(x, y.as_usize()) else {
+                    return State::SegmentationFault
+                };
+                self.files.open(&path, mode)
+            }
+            Instruction::FREAD => {
+                if self.heap_bytes(y, z.as_usize()).is_none() {
+                    return State::SegmentationFault
+                }
+                match self.files.read(x.as_usize(), z.as_usize()) {
+                    Ok(bytes) => {
+                        if !self.store_heap_bytes(y, &bytes) {
+                            return State::SegmentationFault
+                        }
+                        Ok(bytes.len())
+                    }
+                    Err(code) => Err(code)
+                }
+            }
+            Instruction::FWRITE => {
+                let Some(bytes) = self.heap_bytes(y, z.as_usize()) else {
+                    return State::SegmentationFault
+                };
+                self.files.write(x.as_usize(), &bytes)
+            }
+            Instruction::FSEEK => {
+                let offset = match y {
+                    Word::I64(offset) => offset,
+                    word => word.as_usize() as isize,
+                };
+                self.files.seek(x.as_usize(), offset)
+            }
+            Instruction::FCLOSE => self.files.close(x.as_usize()).map(|_| 0),
+            _ => Ok(0)
+        };
+        match res {
+            Ok(value) => self.memory.push(Word::I64(value as isize)),
+            Err(code) => self.memory.push(Word::I64(code)),
+        }
+        return State::OK
+    }
+    fn framebuffer_operation(&mut self,instruction : Instruction) -> State{
+        let mut operands = match instruction {
+            Instruction::PIXEL => 3,
+            Instruction::CLEAR(None) => 1,
+            _ => 0,
+        };
+        let mut words = Vec::with_capacity(operands);
+        while operands > 0 {
+            let Ok(word) = self.memory.pop() else {
+                return State::StackUnderflow
+            };
+            words.push(word);
+            operands -= 1;
+        }
+        if let Instruction::CLEAR(Some(e)) = &instruction {
+            words.push(self.get_either(e.clone()));
+        }
+        let Some(framebuffer) = self.framebuffer.as_mut() else {
+            return State::IllegalInstruction
+        };
+        match instruction {
+            Instruction::PIXEL => {
+                let (color,y,x) = (words[0],words[1],words[2]);
+                if !framebuffer.set_pixel(x.as_usize(), y.as_usize(), color.as_usize() as u32) {
                     return State::SegmentationFault
                 }
             }
+            Instruction::CLEAR(_) => {
+                framebuffer.clear(words[0].as_usize() as u32);
+            }
+            Instruction::PRESENT => {
+                if framebuffer.present().is_err() {
+                    return State::IOError
+                }
+            }
+            _ => {}
+        }
+        return State::OK
+    }
+    fn interrupt_operation(&mut self,instruction : Instruction) -> State{
+        let number = match instruction {
+            Instruction::VECTOR(number, _)
+                | Instruction::RAISE(number)
+                | Instruction::TIMER(_, number)
+                | Instruction::IMASK(Some(number))
+                | Instruction::IUNMASK(Some(number)) => Some(number),
+            _ => None,
+        };
+        if number.is_some_and(|number| number >= INTERRUPTS) {
+            return State::IllegalInstruction
+        }
+        let bits = number.map_or(u64::MAX, |number| 1 << number);
+        match instruction {
+            Instruction::VECTOR(number, label) => {
+                self.vectors.insert(number, label);
+            }
+            Instruction::RAISE(number) => {
+                self.interrupts.raise(number);
+            }
+            Instruction::IRET => {
+                let Some(registers) = self.interrupted.pop() else {
+                    return State::IllegalInstruction
+                };
+                // the stack is shared with the interrupted code and stays as it is
+                let (ts,bs) = (self.memory.registers.Ts,self.memory.registers.Bs);
+                self.memory.registers = registers;
+                self.memory.registers.Ts = ts;
+                self.memory.registers.Bs = bs;
+            }
+            Instruction::TIMER(period, number) => {
+                self.ticks = 0;
+                self.timer = if period == 0 { None } else { Some((period,number)) };
+            }
+            Instruction::IMASK(_) => {
+                self.mask |= bits;
+            }
+            Instruction::IUNMASK(_) => {
+                self.mask &= !bits;
+            }
+            _ => {}
+        }
+        return State::OK
+    }
+    fn channel_operation(&mut self,instruction : Instruction) -> State{
+        match instruction {
+            Instruction::CHANNEW(capacity) => {
+                let channel = self.hub.channel(capacity);
+                self.memory.push(channel);
+            }
+            Instruction::SEND | Instruction::TRYSEND => {
+                let Ok(word) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                let Ok(channel) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                let sent = match self.hub.send(channel, word) {
+                    Ok(_) => true,
+                    Err(ChannelError::InvalidChannel) => return State::SegmentationFault,
+                    Err(ChannelError::Blocked(progress)) => {
+                        if let Instruction::SEND = instruction {
+                            self.memory.push(channel);
+                            self.memory.push(word);
+                            self.blocked_at = Some(progress);
+                            return State::Blocked;
+                        }
+                        false
+                    }
+                };
+                if let Instruction::TRYSEND = instruction {
+                    self.memory.push(Word::BOOL(sent));
+                }
+            }
+            Instruction::RECV | Instruction::TRYRECV => {
+                let Ok(channel) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                match self.hub.recv(channel) {
+                    Ok(word) => {
+                        self.memory.push(word);
+                        if let Instruction::TRYRECV = instruction {
+                            self.memory.push(Word::BOOL(true));
+                        }
+                    }
+                    Err(ChannelError::InvalidChannel) => return State::SegmentationFault,
+                    Err(ChannelError::Blocked(progress)) => {
+                        if let Instruction::RECV = instruction {
+                            self.memory.push(channel);
+                            self.blocked_at = Some(progress);
+                            return State::Blocked;
+                        }
+                        self.memory.push(Word::BOOL(false));
+                    }
+                }
+            }
+            Instruction::SPAWNVM(label, size) => {
+                if !self.scheduled {
+                    return State::IllegalInstruction
+                }
+                let mut memory = Memory::build();
+                memory.registers.Ni = Word::U64(label);
+                let mut args = Vec::with_capacity(size);
+                for _ in 0..size {
+                    let Ok(word) = self.memory.pop() else {
+                        return State::StackUnderflow
+                    };
+                    args.push(word);
+                }
+                for word in args.into_iter().rev() {
+                    memory.push(word);
+                }
+                let mut vulkyn = Vulkyn::load(Program { instructions : Vec::new() });
+                vulkyn.memory = memory;
+                vulkyn.program = self.program.clone();
+                vulkyn.checked = self.checked;
+                vulkyn.attach(self.hub.clone());
+                let id = self.hub.spawn(vulkyn);
+                self.memory.push(Word::U64(id));
+            }
             _ => {}
         }
         return State::OK
     }
+    fn call(&mut self,target : Word,args : usize) -> State{
+        let Ok(_) = self.memory.insert(self.memory.registers.Li, args) else {
+            return State::StackUnderflow
+        };
+        let Ok(addr) = self.memory.insert(self.memory.registers.Ni, args) else {
+            return State::StackUnderflow
+        };
+        self.memory.registers.Li = addr;
+        self.memory.registers.Ni = target;
+        return State::OK
+    }
 
-    fn conversion_operation(&mut self,instruction : Instruction) -> State{
+    fn conversion_operation(&mut self,instruction : Instruction,dest : Option<Register>) -> State{
         match instruction {
             Instruction::F2I 
                 | Instruction::U2I
@@ -907,6 +2552,16 @@ impl Vulkyn {
                 };
                 self.memory.push(word.to_i64());
             }
+            Instruction::I2F
+                | Instruction::U2F
+                | Instruction::C2F
+                | Instruction::B2F
+                => {
+                let Ok(word) = self.memory.pop() else {
+                    return State::StackUnderflow
+                };
+                self.memory.push(word.to_f64());
+            }
             Instruction::F2U
                 | Instruction::I2U
                 | Instruction::C2U
@@ -935,7 +2590,10 @@ impl Vulkyn {
                 let Ok(word) = self.memory.pop() else {
                     return State::StackUnderflow
                 };
-                self.memory.push(word.to_char());
+                let Some(word) = word.to_char() else {
+                    return State::InvalidConversion
+                };
+                self.memory.push(word);
             }
             Instruction::RF2I(e) 
                 | Instruction::RC2I(e) 
@@ -943,7 +2601,15 @@ impl Vulkyn {
                 | Instruction::RU2I(e) 
                 => {
                 let word = self.get_either(e);
-                self.memory.push(word.to_i64());
+                self.output(dest, word.to_i64());
+            }
+            Instruction::RI2F(e)
+                | Instruction::RU2F(e)
+                | Instruction::RC2F(e)
+                | Instruction::RB2F(e)
+                 => {
+                let word = self.get_either(e);
+                self.output(dest, word.to_f64());
             }
             Instruction::RF2U(e)
                 | Instruction::RC2U(e) 
@@ -951,7 +2617,7 @@ impl Vulkyn {
                 | Instruction::RI2U(e) 
                  => {
                 let word = self.get_either(e);
-                self.memory.push(word.to_u64());
+                self.output(dest, word.to_u64());
             }
             Instruction::RF2B(e) 
                 | Instruction::RC2B(e) 
@@ -959,15 +2625,17 @@ impl Vulkyn {
                 | Instruction::RI2B(e) 
                  => {
                 let word = self.get_either(e);
-                self.memory.push(word.to_bool());
+                self.output(dest, word.to_bool());
             }
             Instruction::RF2C(e) 
                 | Instruction::RB2C(e) 
                 | Instruction::RU2C(e) 
                 | Instruction::RI2C(e) 
                  => {
-                let word = self.get_either(e);
-                self.memory.push(word.to_char());
+                let Some(word) = self.get_either(e).to_char() else {
+                    return State::InvalidConversion
+                };
+                self.output(dest, word);
             }
             _ => {}
         }
@@ -992,6 +2660,15 @@ impl Vulkyn {
         if (self.memory.registers.Fl & FLAG_DZ) == FLAG_DZ{
             println!("Error : Divizion per zero")
         }
+        if (self.memory.registers.Fl & FLAG_IC) == FLAG_IC{
+            println!("Error : invalid conversion")
+        }
+        if (self.memory.registers.Fl & FLAG_DL) == FLAG_DL{
+            println!("Error : deadlock")
+        }
+        if (self.memory.registers.Fl & FLAG_IO) == FLAG_IO{
+            println!("Error : input output")
+        }
         //dbg!(&self.memory);
     }
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use serde::{Serialize, Deserialize};
-use std::{ops::{BitAnd, Add, Sub, Mul, Div, Rem, BitOr, Shl, Shr, BitXor}, hash::{Hash, Hasher}, fmt::Display};
+use std::{ops::{BitAnd, Add, Sub, Mul, Div, Rem, BitOr, Shl, Shr, BitXor}, hash::{Hash, Hasher}, fmt::Display, cmp::Ordering};
 
 
 #[derive(Debug,Clone,Copy,Serialize,Deserialize)]
@@ -9,6 +9,8 @@ pub enum Word{
     F64(f64),
     CHAR(char),
     BOOL(bool),
+    // heap address returned by ALLOC, keeps its provenance through + and -
+    PTR(usize),
 }
 impl Word {
     pub fn init() -> Self{
@@ -19,8 +21,9 @@ impl Word {
             Word::U64(w) => w == 0,
             Word::I64(w) => w == 0,
             Word::F64(w) => w == 0.0,
-            Word::CHAR(w) => w as u8 == 0,
+            Word::CHAR(w) => w == '\0',
             Word::BOOL(w) => !w,
+            Word::PTR(w) => w == 0,
         }
     }
 
@@ -29,8 +32,20 @@ impl Word {
             Word::U64(w) => w ,
             Word::I64(w) => w as usize,
             Word::F64(w) => w as usize,
-            Word::CHAR(w) => (w as u8) as usize,
+            Word::CHAR(w) => w as usize,
             Word::BOOL(w) => w as usize,
+            Word::PTR(w) => w,
+        }
+    }
+    // Raw bits of the value, floats keep their IEEE 754 representation
+    pub fn to_bits(self) -> u64 {
+        match self {
+            Word::U64(w) => w as u64,
+            Word::I64(w) => w as u64,
+            Word::F64(w) => w.to_bits(),
+            Word::CHAR(w) => w as u64,
+            Word::BOOL(w) => w as u64,
+            Word::PTR(w) => w as u64,
         }
     }
     pub fn to_u64(self) -> Self {
@@ -38,8 +53,9 @@ impl Word {
             Word::U64(w) => self,
             Word::I64(w) => Word::U64(w as usize),
             Word::F64(w) => Word::U64(w as usize),
-            Word::CHAR(w) => Word::U64((w as u8) as usize),
+            Word::CHAR(w) => Word::U64(w as usize),
             Word::BOOL(w) =>Word::U64(w as usize),
+            Word::PTR(w) => Word::U64(w),
         }
     }
     pub fn to_f64(self) -> Self {
@@ -47,8 +63,9 @@ impl Word {
             Word::U64(w) => Word::F64(w as f64),
             Word::I64(w) => Word::F64(w as f64),
             Word::F64(w) => self,
-            Word::CHAR(w) => Word::F64((w as u8) as f64),
+            Word::CHAR(w) => Word::F64((w as u32) as f64),
             Word::BOOL(w) =>Word::F64((w as usize) as f64),
+            Word::PTR(w) => Word::F64(w as f64),
         }
     }
     pub fn to_i64(self) -> Self {
@@ -56,26 +73,36 @@ impl Word {
             Word::U64(w) => Word::I64(w as isize),
             Word::I64(w) => self,
             Word::F64(w) => Word::I64(w as isize),
-            Word::CHAR(w) => Word::I64((w as u8) as isize),
+            Word::CHAR(w) => Word::I64(w as isize),
             Word::BOOL(w) =>Word::I64((w as usize) as isize),
+            Word::PTR(w) => Word::I64(w as isize),
         }
     }
-    pub fn to_char(self) -> Self {
-        match self {
-            Word::U64(w) => Word::CHAR((w as u8) as char),
-            Word::I64(w) => Word::CHAR((w as u8) as char),
-            Word::F64(w) => Word::CHAR((w as u8) as char),
-            Word::CHAR(w) => self,
-            Word::BOOL(w) => Word::CHAR((w as u8) as char),
-        }
+    // None when the value is not a valid unicode scalar value
+    pub fn to_char(self) -> Option<Self> {
+        let code = match self {
+            Word::U64(w) => u32::try_from(w).ok(),
+            Word::I64(w) => u32::try_from(w).ok(),
+            Word::F64(w) => {
+                if w.fract() != 0.0 {
+                    return None;
+                }
+                u32::try_from(w as i64).ok()
+            }
+            Word::CHAR(_) => return Some(self),
+            Word::BOOL(w) => Some(w as u32),
+            Word::PTR(w) => u32::try_from(w).ok(),
+        };
+        return code.and_then(char::from_u32).map(Word::CHAR);
     }
     pub fn to_bool(self) -> Self {
         match self {
             Word::U64(w) => Word::BOOL((w as u8) != 0),
             Word::I64(w) => Word::BOOL((w as u8) != 0),
             Word::F64(w) => Word::BOOL((w as u8)  != 0),
-            Word::CHAR(w) => Word::BOOL((w as u8)  != 0),
+            Word::CHAR(w) => Word::BOOL(w != '\0'),
             Word::BOOL(w) => self,
+            Word::PTR(w) => Word::BOOL(w != 0),
         }
     }
     pub fn and(&self, other: &Self) -> Self {
@@ -87,7 +114,205 @@ impl Word {
     pub fn neg(&self) -> Self {
         return Word::BOOL(self.is_zero()); 
     }
+    pub fn is_negative(self) -> bool {
+        match self {
+            Word::I64(w) => w < 0,
+            Word::F64(w) => w < 0.0,
+            _ => false,
+        }
+    }
+    // The overflowing_* operations return (result, carry, overflow) :
+    // carry is the unsigned overflow and overflow the signed one
+    // of the same 64 bits, as a cpu would set them.
+    pub fn overflowing_add(self, rhs: Self) -> (Self,bool,bool) {
+        match (self,rhs) {
+            (Word::U64(x), Word::U64(y)) => {
+                let (res,carry) = x.overflowing_add(y);
+                let (_,overflow) = (x as isize).overflowing_add(y as isize);
+                (Word::U64(res),carry,overflow)
+            }
+            (Word::I64(x), Word::I64(y)) => {
+                let (res,overflow) = x.overflowing_add(y);
+                let (_,carry) = (x as usize).overflowing_add(y as usize);
+                (Word::I64(res),carry,overflow)
+            }
+            _ => (self + rhs,false,false)
+        }
+    }
+    pub fn overflowing_sub(self, rhs: Self) -> (Self,bool,bool) {
+        match (self,rhs) {
+            (Word::U64(x), Word::U64(y)) => {
+                let (res,carry) = x.overflowing_sub(y);
+                let (_,overflow) = (x as isize).overflowing_sub(y as isize);
+                (Word::U64(res),carry,overflow)
+            }
+            (Word::I64(x), Word::I64(y)) => {
+                let (res,overflow) = x.overflowing_sub(y);
+                let (_,carry) = (x as usize).overflowing_sub(y as usize);
+                (Word::I64(res),carry,overflow)
+            }
+            _ => (self - rhs,false,false)
+        }
+    }
+    pub fn overflowing_mul(self, rhs: Self) -> (Self,bool,bool) {
+        match (self,rhs) {
+            (Word::U64(x), Word::U64(y)) => {
+                let (res,carry) = x.overflowing_mul(y);
+                let (_,overflow) = (x as isize).overflowing_mul(y as isize);
+                (Word::U64(res),carry,overflow)
+            }
+            (Word::I64(x), Word::I64(y)) => {
+                let (res,overflow) = x.overflowing_mul(y);
+                let (_,carry) = (x as usize).overflowing_mul(y as usize);
+                (Word::I64(res),carry,overflow)
+            }
+            _ => (self * rhs,false,false)
+        }
+    }
+
+    /* FLOAT MATH */
+    pub fn as_f64(self) -> f64 {
+        match self.to_f64() {
+            Word::F64(w) => w,
+            _ => 0.0,
+        }
+    }
+    fn float_map(self, f : fn(f64) -> f64) -> Self {
+        Word::F64(f(self.as_f64()))
+    }
+    pub fn sqrt(self) -> Self {
+        self.float_map(f64::sqrt)
+    }
+    pub fn pow(self, rhs: Self) -> Self {
+        Word::F64(self.as_f64().powf(rhs.as_f64()))
+    }
+    pub fn exp(self) -> Self {
+        self.float_map(f64::exp)
+    }
+    pub fn ln(self) -> Self {
+        self.float_map(f64::ln)
+    }
+    pub fn sin(self) -> Self {
+        self.float_map(f64::sin)
+    }
+    pub fn cos(self) -> Self {
+        self.float_map(f64::cos)
+    }
+    pub fn tan(self) -> Self {
+        self.float_map(f64::tan)
+    }
+    pub fn atan2(self, rhs: Self) -> Self {
+        Word::F64(self.as_f64().atan2(rhs.as_f64()))
+    }
+    pub fn floor(self) -> Self {
+        self.float_map(f64::floor)
+    }
+    pub fn ceil(self) -> Self {
+        self.float_map(f64::ceil)
+    }
+    pub fn round(self) -> Self {
+        self.float_map(f64::round)
+    }
+    pub fn abs(self) -> Self {
+        match self {
+            Word::I64(w) => Word::I64(w.wrapping_abs()),
+            Word::F64(w) => Word::F64(w.abs()),
+            _ => self,
+        }
+    }
+    pub fn min(self, rhs: Self) -> Self {
+        match (self,rhs) {
+            (Word::F64(x), Word::F64(y)) => Word::F64(x.min(y)),
+            _ => if rhs.int_cmp(self) == Some(Ordering::Less) { rhs } else { self }
+        }
+    }
+    pub fn max(self, rhs: Self) -> Self {
+        match (self,rhs) {
+            (Word::F64(x), Word::F64(y)) => Word::F64(x.max(y)),
+            _ => if rhs.int_cmp(self) == Some(Ordering::Greater) { rhs } else { self }
+        }
+    }
+    // Like partial_cmp, but U64 and I64 also compare with each other
+    fn int_cmp(self, rhs: Self) -> Option<Ordering> {
+        match (self,rhs) {
+            (Word::U64(x), Word::I64(y)) => Some((x as i128).cmp(&(y as i128))),
+            (Word::I64(x), Word::U64(y)) => Some((x as i128).cmp(&(y as i128))),
+            _ => self.partial_cmp(&rhs)
+        }
+    }
+    pub fn is_nan(self) -> Self {
+        match self {
+            Word::F64(w) => Word::BOOL(w.is_nan()),
+            _ => Word::BOOL(false),
+        }
+    }
+    pub fn is_inf(self) -> Self {
+        match self {
+            Word::F64(w) => Word::BOOL(w.is_infinite()),
+            _ => Word::BOOL(false),
+        }
+    }
+
+    /* INTEGER OPERATIONS */
+    // Bit counts work on the 64 bits of the value and give a U64
+    pub fn popcnt(self) -> Self {
+        return Word::U64(self.to_bits().count_ones() as usize);
+    }
+    pub fn clz(self) -> Self {
+        return Word::U64(self.to_bits().leading_zeros() as usize);
+    }
+    pub fn ctz(self) -> Self {
+        return Word::U64(self.to_bits().trailing_zeros() as usize);
+    }
+    // Rotates and shifts take the amount modulo 64
+    pub fn rotl(self, rhs: Self) -> Self {
+        let n = rhs.as_usize() as u32;
+        match self {
+            Word::I64(w) => Word::I64(w.rotate_left(n)),
+            _ => Word::U64(self.to_bits().rotate_left(n) as usize),
+        }
+    }
+    pub fn rotr(self, rhs: Self) -> Self {
+        let n = rhs.as_usize() as u32;
+        match self {
+            Word::I64(w) => Word::I64(w.rotate_right(n)),
+            _ => Word::U64(self.to_bits().rotate_right(n) as usize),
+        }
+    }
+    // Shift right copying the sign bit, RSHIFT shifts in zeros
+    pub fn ashr(self, rhs: Self) -> Self {
+        let n = rhs.as_usize() as u32;
+        match self {
+            Word::I64(w) => Word::I64(w.wrapping_shr(n)),
+            _ => Word::U64((self.to_bits() as i64).wrapping_shr(n) as usize),
+        }
+    }
+    pub fn negate(self) -> Self {
+        match self {
+            Word::U64(w) => Word::U64(w.wrapping_neg()),
+            Word::I64(w) => Word::I64(w.wrapping_neg()),
+            Word::F64(w) => Word::F64(-w),
+            _ => Word::I64((self.to_bits() as isize).wrapping_neg()),
+        }
+    }
+    pub fn bnot(self) -> Self {
+        match self {
+            Word::U64(w) => Word::U64(!w),
+            Word::I64(w) => Word::I64(!w),
+            Word::BOOL(w) => Word::BOOL(!w),
+            _ => Word::U64(!self.to_bits() as usize),
+        }
+    }
+}
+// CHAR arithmetic works on unicode scalar values, a result that is not
+// a valid code point gives the replacement character
+fn char_op(x : i64, y : i64, op : fn(i64,i64) -> Option<i64>) -> Word {
+    let code = op(x,y)
+        .and_then(|code| u32::try_from(code).ok())
+        .and_then(char::from_u32);
+    return Word::CHAR(code.unwrap_or(char::REPLACEMENT_CHARACTER));
 }
+
 impl Display for Word {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
@@ -96,6 +321,7 @@ impl Display for Word {
             Word::F64(w) => w.fmt(f),
             Word::CHAR(w) => w.fmt(f),
             Word::BOOL(w) => w.fmt(f),
+            Word::PTR(w) => write!(f, "*{:#x}", w),
         }
     }
 }
@@ -108,6 +334,7 @@ impl PartialEq for Word {
             (Self::F64(l0), Self::F64(r0)) => *l0 == *r0,
             (Self::CHAR(l0), Self::CHAR(r0)) => *l0 == *r0,
             (Self::BOOL(l0), Self::BOOL(r0)) => *l0 == *r0,
+            (Self::PTR(l0), Self::PTR(r0)) => *l0 == *r0,
             _ => false,
         }
     }
@@ -120,6 +347,7 @@ impl PartialOrd for Word {
             (Self::F64(l0), Self::F64(r0)) => l0.partial_cmp(r0),
             (Self::CHAR(l0), Self::CHAR(r0)) => l0.partial_cmp(r0),
             (Self::BOOL(l0), Self::BOOL(r0)) => l0.partial_cmp(r0),
+            (Self::PTR(l0), Self::PTR(r0)) => l0.partial_cmp(r0),
             _ => None,
         }
     }
@@ -150,6 +378,10 @@ impl Hash for Word {
                 state.write_u8(16);
                 w.hash(state);
             }
+            Word::PTR(w) => {
+                state.write_u8(32);
+                w.hash(state);
+            }
         }
     }
 }
@@ -157,34 +389,49 @@ impl Add for Word{
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
+        match (self,rhs) {
+            (Word::PTR(p), n @ (Word::U64(_) | Word::I64(_)))
+                | (n @ (Word::U64(_) | Word::I64(_)), Word::PTR(p)) => {
+                return Word::PTR(p.wrapping_add(n.as_usize().wrapping_mul(16)));
+            }
+            _ => {}
+        }
         match self {
             Word::U64(x) => match rhs {
                 Word::U64(y) => Word::U64(x+y),
                 Word::I64(y) => Word::I64(x as  isize + y),
                 Word::F64(y) => Word::F64(x as f64 + y),
-                Word::CHAR(y) => Word::CHAR(((x as u8) + (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_add),
                 Word::BOOL(y) => Word::U64(x + (y as usize)),
+                Word::PTR(y) => self + Word::U64(y),
             },
             Word::I64(x) =>  match rhs {
                 Word::U64(y) => Word::I64(x+y as isize),
                 Word::I64(y) => Word::I64(x + y),
                 Word::F64(y) => Word::F64(x as f64 + y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) + (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_add),
                 Word::BOOL(y) => Word::I64(x + (y as isize)),
+                Word::PTR(y) => self + Word::U64(y),
             },
             Word::F64(x) => match rhs {
                 Word::U64(y) => Word::F64(x + y as f64),
                 Word::I64(y) => Word::F64(x + y as f64),
                 Word::F64(y) => Word::F64(x + y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) + (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_add),
                 Word::BOOL(y) => Word::F64(x + ((y as usize) as f64)),
+                Word::PTR(y) => self + Word::U64(y),
             },
             Word::CHAR(x) => match rhs {
-                Word::U64(y) => Word::CHAR(((x as u8) + (y as u8)) as char ),
-                Word::I64(y) => Word::CHAR(((x as u8) + (y as u8)) as char ),
-                Word::F64(y) => Word::CHAR(((x as u8) + (y as u8)) as char ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) + (y as u8)) as char ),
-                Word::BOOL(y) => Word::CHAR(((x as u8) + (y as u8)) as char ),
+                Word::U64(y) => char_op(x as i64, y as i64, i64::checked_add),
+                Word::I64(y) => char_op(x as i64, y as i64, i64::checked_add),
+                Word::F64(y) => char_op(x as i64, y as i64, i64::checked_add),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_add),
+                Word::BOOL(y) => char_op(x as i64, y as i64, i64::checked_add),
+                Word::PTR(y) => self + Word::U64(y),
+            },
+            Word::PTR(x) => match rhs {
+                Word::PTR(y) => Word::U64(x.wrapping_add(y)),
+                _ => Word::U64(x) + rhs,
             },
             Word::BOOL(x) => match rhs {
                 _ => Word::BOOL(x),
@@ -197,36 +444,52 @@ impl Sub for Word{
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
+        match (self,rhs) {
+            (Word::PTR(p), n @ (Word::U64(_) | Word::I64(_))) => {
+                return Word::PTR(p.wrapping_sub(n.as_usize().wrapping_mul(16)));
+            }
+            // distance in words between two pointers
+            (Word::PTR(p), Word::PTR(q)) => {
+                return Word::I64((p as isize).wrapping_sub(q as isize) / 16);
+            }
+            _ => {}
+        }
         match self {
             Word::U64(x) => match rhs {
                 Word::U64(y) => Word::U64(x-y),
                 Word::I64(y) => Word::I64(x as  isize - y),
                 Word::F64(y) => Word::F64(x as f64 - y),
-                Word::CHAR(y) => Word::CHAR(((x as u8) - (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_sub),
                 Word::BOOL(y) => Word::U64(x - (y as usize)),
+                Word::PTR(y) => self - Word::U64(y),
             },
             Word::I64(x) =>  match rhs {
                 Word::U64(y) => Word::I64(x-y as isize),
                 Word::I64(y) => Word::I64(x - y),
                 Word::F64(y) => Word::F64(x as f64 - y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) - (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_sub),
                 Word::BOOL(y) => Word::I64(x - (y as isize)),
+                Word::PTR(y) => self - Word::U64(y),
             },
             Word::F64(x) => match rhs {
                 Word::U64(y) => Word::F64(x - y as f64),
                 Word::I64(y) => Word::F64(x - y as f64),
                 Word::F64(y) => Word::F64(x - y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) - (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_sub),
                 Word::BOOL(y) => Word::F64(x - ((y as usize) as f64)),
+                Word::PTR(y) => self - Word::U64(y),
             },
             Word::CHAR(x) => match rhs {
-                Word::U64(y) => Word::CHAR(((x as u8) - (y as u8)) as char ),
-                Word::I64(y) => Word::CHAR(((x as u8) - (y as u8)) as char ),
-                Word::F64(y) => Word::CHAR(((x as u8) - (y as u8)) as char ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) - (y as u8)) as char ),
-                Word::BOOL(y) => Word::CHAR(((x as u8) - (y as u8)) as char ),
+                Word::U64(y) => char_op(x as i64, y as i64, i64::checked_sub),
+                Word::I64(y) => char_op(x as i64, y as i64, i64::checked_sub),
+                Word::F64(y) => char_op(x as i64, y as i64, i64::checked_sub),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_sub),
+                Word::BOOL(y) => char_op(x as i64, y as i64, i64::checked_sub),
+                Word::PTR(y) => self - Word::U64(y),
             },
 
+            Word::PTR(x) => Word::U64(x) - rhs,
+
             Word::BOOL(x) => match rhs {
                 _ => Word::BOOL(x),
             },
@@ -243,30 +506,35 @@ impl Mul for Word{
                 Word::U64(y) => Word::U64(x*y),
                 Word::I64(y) => Word::I64(x as  isize * y),
                 Word::F64(y) => Word::F64(x as f64 * y),
-                Word::CHAR(y) => Word::CHAR(((x as u8) * (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_mul),
                 Word::BOOL(y) => Word::U64(x * (y as usize)),
+                Word::PTR(y) => self * Word::U64(y),
             },
             Word::I64(x) =>  match rhs {
                 Word::U64(y) => Word::I64(x*y as isize),
                 Word::I64(y) => Word::I64(x * y),
                 Word::F64(y) => Word::F64(x as f64 * y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) * (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_mul),
                 Word::BOOL(y) => Word::I64(x * (y as isize)),
+                Word::PTR(y) => self * Word::U64(y),
             },
             Word::F64(x) => match rhs {
                 Word::U64(y) => Word::F64(x * y as f64),
                 Word::I64(y) => Word::F64(x * y as f64),
                 Word::F64(y) => Word::F64(x * y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) * (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_mul),
                 Word::BOOL(y) => Word::F64(x * ((y as usize) as f64)),
+                Word::PTR(y) => self * Word::U64(y),
             },
             Word::CHAR(x) => match rhs {
-                Word::U64(y) => Word::CHAR(((x as u8) * (y as u8)) as char ),
-                Word::I64(y) => Word::CHAR(((x as u8) * (y as u8)) as char ),
-                Word::F64(y) => Word::CHAR(((x as u8) * (y as u8)) as char ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) * (y as u8)) as char ),
-                Word::BOOL(y) => Word::CHAR(((x as u8) * (y as u8)) as char ),
+                Word::U64(y) => char_op(x as i64, y as i64, i64::checked_mul),
+                Word::I64(y) => char_op(x as i64, y as i64, i64::checked_mul),
+                Word::F64(y) => char_op(x as i64, y as i64, i64::checked_mul),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_mul),
+                Word::BOOL(y) => char_op(x as i64, y as i64, i64::checked_mul),
+                Word::PTR(y) => self * Word::U64(y),
             },
+            Word::PTR(x) => Word::U64(x) * rhs,
             Word::BOOL(x) => match rhs {
                 _ => Word::BOOL(x),
             },
@@ -283,30 +551,35 @@ impl Div for Word{
                 Word::U64(y) => Word::U64(x/y),
                 Word::I64(y) => Word::I64(x as  isize / y),
                 Word::F64(y) => Word::F64(x as f64 / y),
-                Word::CHAR(y) => Word::CHAR(((x as u8) / (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_div),
                 Word::BOOL(y) => Word::U64(x / (y as usize)),
+                Word::PTR(y) => self / Word::U64(y),
             },
             Word::I64(x) =>  match rhs {
                 Word::U64(y) => Word::I64(x/y as isize),
                 Word::I64(y) => Word::I64(x / y),
                 Word::F64(y) => Word::F64(x as f64 / y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) / (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_div),
                 Word::BOOL(y) => Word::I64(x / (y as isize)),
+                Word::PTR(y) => self / Word::U64(y),
             },
             Word::F64(x) => match rhs {
                 Word::U64(y) => Word::F64(x / y as f64),
                 Word::I64(y) => Word::F64(x / y as f64),
                 Word::F64(y) => Word::F64(x / y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) / (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_div),
                 Word::BOOL(y) => Word::F64(x / ((y as usize) as f64)),
+                Word::PTR(y) => self / Word::U64(y),
             },
             Word::CHAR(x) => match rhs {
-                Word::U64(y) => Word::CHAR(((x as u8) / (y as u8)) as char ),
-                Word::I64(y) => Word::CHAR(((x as u8) / (y as u8)) as char ),
-                Word::F64(y) => Word::CHAR(((x as u8) / (y as u8)) as char ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) / (y as u8)) as char ),
+                Word::U64(y) => char_op(x as i64, y as i64, i64::checked_div),
+                Word::I64(y) => char_op(x as i64, y as i64, i64::checked_div),
+                Word::F64(y) => char_op(x as i64, y as i64, i64::checked_div),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_div),
                 Word::BOOL(y) => Word::CHAR(x),
+                Word::PTR(y) => self / Word::U64(y),
             },
+            Word::PTR(x) => Word::U64(x) / rhs,
             Word::BOOL(x) => match rhs {
                 _ => Word::BOOL(x),
             },
@@ -323,30 +596,35 @@ impl Rem for Word{
                 Word::U64(y) => Word::U64(x%y),
                 Word::I64(y) => Word::I64(x as  isize % y),
                 Word::F64(y) => Word::F64(x as f64 % y),
-                Word::CHAR(y) => Word::CHAR(((x as u8) % (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_rem),
                 Word::BOOL(y) => Word::U64(x % (y as usize)),
+                Word::PTR(y) => self % Word::U64(y),
             },
             Word::I64(x) =>  match rhs {
                 Word::U64(y) => Word::I64(x%y as isize),
                 Word::I64(y) => Word::I64(x % y),
                 Word::F64(y) => Word::F64(x as f64 % y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) % (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_rem),
                 Word::BOOL(y) => Word::I64(x % (y as isize)),
+                Word::PTR(y) => self % Word::U64(y),
             },
             Word::F64(x) => match rhs {
                 Word::U64(y) => Word::F64(x % y as f64),
                 Word::I64(y) => Word::F64(x % y as f64),
                 Word::F64(y) => Word::F64(x % y ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) % (y as u8)) as char ),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_rem),
                 Word::BOOL(y) => Word::F64(x % ((y as usize) as f64)),
+                Word::PTR(y) => self % Word::U64(y),
             },
             Word::CHAR(x) => match rhs {
-                Word::U64(y) => Word::CHAR(((x as u8) % (y as u8)) as char ),
-                Word::I64(y) => Word::CHAR(((x as u8) % (y as u8)) as char ),
-                Word::F64(y) => Word::CHAR(((x as u8) % (y as u8)) as char ),
-                Word::CHAR(y) => Word::CHAR(((x as u8) % (y as u8)) as char ),
-                Word::BOOL(y) => Word::CHAR(((x as u8) % (y as u8)) as char ),
+                Word::U64(y) => char_op(x as i64, y as i64, i64::checked_rem),
+                Word::I64(y) => char_op(x as i64, y as i64, i64::checked_rem),
+                Word::F64(y) => char_op(x as i64, y as i64, i64::checked_rem),
+                Word::CHAR(y) => char_op(x as i64, y as i64, i64::checked_rem),
+                Word::BOOL(y) => char_op(x as i64, y as i64, i64::checked_rem),
+                Word::PTR(y) => self % Word::U64(y),
             },
+            Word::PTR(x) => Word::U64(x) % rhs,
             Word::BOOL(x) => match rhs {
                 _ => Word::BOOL(x),
             },
@@ -365,6 +643,7 @@ impl BitAnd for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let self_bytes = {
@@ -374,6 +653,7 @@ impl BitAnd for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let res = self_bytes & cmp_bytes;
@@ -392,6 +672,7 @@ impl BitOr for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let self_bytes = {
@@ -401,6 +682,7 @@ impl BitOr for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let res = self_bytes | cmp_bytes;
@@ -420,6 +702,7 @@ impl BitXor for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let self_bytes = {
@@ -429,6 +712,7 @@ impl BitXor for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let res = self_bytes ^ cmp_bytes;
@@ -446,6 +730,7 @@ impl Shl for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let self_bytes = {
@@ -455,6 +740,7 @@ impl Shl for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let res = self_bytes << cmp_bytes;
@@ -473,6 +759,7 @@ impl Shr for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let self_bytes = {
@@ -482,6 +769,7 @@ impl Shr for Word {
                 Word::F64(w) => w as usize,
                 Word::CHAR(w) => w as usize,
                 Word::BOOL(w) => w as usize,
+                Word::PTR(w) => w,
             }
         };
         let res = self_bytes >> cmp_bytes;